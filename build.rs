@@ -0,0 +1,59 @@
+//! Generates one test per example per part from `examples/dayNN/answers.toml`. The tests are
+//! included by `tests/examples.rs`.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+  println!("cargo:rerun-if-changed=examples");
+
+  let mut tests = String::new();
+  let mut days: Vec<_> = fs::read_dir("examples")
+    .map(|dirs| dirs.flatten().map(|dir| dir.path()).collect())
+    .unwrap_or_default();
+  days.sort();
+
+  for dir in days {
+    let name = dir.file_name().unwrap().to_string_lossy().into_owned();
+    let day: u8 = match name.strip_prefix("day").and_then(|day| day.parse().ok()) {
+      Some(day) => day,
+      None => continue,
+    };
+    let answers = dir.join("answers.toml");
+    let text = match fs::read_to_string(&answers) {
+      Ok(text) => text,
+      Err(_) => continue,
+    };
+    let value: toml::Value = text
+      .parse()
+      .unwrap_or_else(|err| panic!("{}: {}", answers.display(), err));
+
+    let inputs = value.get("input").and_then(|inputs| inputs.as_array());
+    for input in inputs.into_iter().flatten() {
+      let file = input["file"].as_str().expect("file must be a string");
+      let stem = Path::new(file).file_stem().unwrap().to_string_lossy();
+      let stem: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+
+      for part in 1..=2 {
+        let expected = match input.get(format!("part{}", part).as_str()) {
+          Some(expected) => expected.as_str().expect("answers must be strings"),
+          None => continue,
+        };
+        writeln!(
+          tests,
+          "#[test]\nfn day{:02}_{}_part{}() {{\n  check({}, {:?}, {}, {:?});\n}}\n",
+          day, stem, part, day, file, part, expected
+        )
+        .unwrap();
+      }
+    }
+  }
+
+  let out = Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs");
+  fs::write(out, tests).unwrap();
+}
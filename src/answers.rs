@@ -0,0 +1,118 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// An input file and the known answers for it.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Entry {
+  pub file: PathBuf,
+  pub part1: Option<String>,
+  pub part2: Option<String>,
+}
+
+impl Entry {
+  /// An input file with no known answers.
+  pub fn unknown(file: impl Into<PathBuf>) -> Self {
+    Self {
+      file: file.into(),
+      part1: None,
+      part2: None,
+    }
+  }
+
+  pub fn expected(&self, part: u8) -> Option<&String> {
+    match part {
+      1 => self.part1.as_ref(),
+      2 => self.part2.as_ref(),
+      _ => None,
+    }
+  }
+}
+
+/// The contents of an answers file, e.g. `answers/day01.toml`:
+///
+/// ```toml
+/// [[input]]
+/// file = "inputs/day01.txt"
+/// part1 = "646779"
+/// part2 = "246191688"
+/// ```
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct Answers {
+  #[serde(default, rename = "input")]
+  pub inputs: Vec<Entry>,
+}
+
+impl Answers {
+  pub fn parse(text: &str) -> io::Result<Self> {
+    toml::from_str(text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+  }
+
+  /// Loads `path`, or returns None if it does not exist.
+  pub fn load(path: &Path) -> io::Result<Option<Self>> {
+    match fs::read_to_string(path) {
+      Ok(text) => Self::parse(&text).map(Some),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+      Err(err) => Err(err),
+    }
+  }
+}
+
+pub fn default_input(day: u8) -> PathBuf {
+  PathBuf::from(format!("inputs/day{:02}.txt", day))
+}
+
+pub fn answers_path(day: u8) -> PathBuf {
+  PathBuf::from(format!("answers/day{:02}.toml", day))
+}
+
+/// Every input registered for `day` in its answers file. Without an answers file, or with one
+/// that lists no inputs, the day's default input is checked against no known answers, so the
+/// list is never empty.
+pub fn inputs_for(day: u8) -> io::Result<Vec<Entry>> {
+  match Answers::load(&answers_path(day))? {
+    Some(answers) if !answers.inputs.is_empty() => Ok(answers.inputs),
+    _ => Ok(vec![Entry::unknown(default_input(day))]),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::answers::{Answers, Entry};
+
+  #[test]
+  fn parse() {
+    let answers = Answers::parse(
+      r#"
+      [[input]]
+      file = "inputs/day01.txt"
+      part1 = "1"
+      part2 = "2"
+
+      [[input]]
+      file = "inputs/alice/day01.txt"
+      part1 = "3"
+      "#,
+    )
+    .unwrap();
+
+    assert_eq!(answers.inputs.len(), 2);
+    assert_eq!(answers.inputs[0].expected(2), Some(&"2".to_string()));
+    assert_eq!(
+      answers.inputs[1],
+      Entry {
+        file: "inputs/alice/day01.txt".into(),
+        part1: Some("3".to_string()),
+        part2: None,
+      }
+    );
+  }
+
+  #[test]
+  fn malformed() {
+    assert!(Answers::parse("[[input]]\npart1 = \"1\"\n").is_err());
+    assert_eq!(Answers::parse("").unwrap(), Answers::default());
+  }
+}
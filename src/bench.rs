@@ -0,0 +1,253 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::problem::Solver;
+use crate::runner;
+
+/// Summary statistics over a set of samples, in nanoseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+  pub min_ns: u64,
+  pub median_ns: u64,
+  pub mean_ns: u64,
+  pub p95_ns: u64,
+}
+
+impl Stats {
+  pub fn from_samples(samples: &[Duration]) -> Self {
+    if samples.is_empty() {
+      return Self::default();
+    }
+    let mut nanos: Vec<u64> = samples.iter().map(|d| d.as_nanos() as u64).collect();
+    nanos.sort_unstable();
+
+    let len = nanos.len();
+    let median = if len.is_multiple_of(2) {
+      (nanos[len / 2 - 1] + nanos[len / 2]) / 2
+    } else {
+      nanos[len / 2]
+    };
+    // Nearest-rank percentile.
+    let p95_rank = (len * 95).div_ceil(100);
+
+    Self {
+      min_ns: nanos[0],
+      median_ns: median,
+      mean_ns: nanos.iter().sum::<u64>() / len as u64,
+      p95_ns: nanos[p95_rank - 1],
+    }
+  }
+}
+
+/// Benchmark results for a single day.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DayBench {
+  pub year: u16,
+  pub day: u8,
+  pub iterations: usize,
+  pub parse: Stats,
+  /// None if the part is not implemented.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub part_one: Option<Stats>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub part_two: Option<Stats>,
+}
+
+impl DayBench {
+  /// The stats of each benchmarked stage, labelled for display.
+  pub fn stages(&self) -> Vec<(&'static str, &Stats)> {
+    let parts = [("part 1", &self.part_one), ("part 2", &self.part_two)];
+    let parts = parts.iter().filter_map(|&(stage, stats)| Some((stage, stats.as_ref()?)));
+    std::iter::once(("parse", &self.parse)).chain(parts).collect()
+  }
+}
+
+/// Parses and solves `input` `iterations` times. Parts that are not implemented are skipped.
+/// Fails on any other error, since timing an error path is not useful.
+pub fn bench(
+  solver: &dyn Solver,
+  input: &str,
+  year: u16,
+  day: u8,
+  iterations: usize,
+) -> Result<DayBench> {
+  let mut parse = Vec::with_capacity(iterations);
+  let mut part_one = Some(Vec::with_capacity(iterations));
+  let mut part_two = Some(Vec::with_capacity(iterations));
+
+  for _ in 0..iterations {
+    let outcome = runner::solve(solver, input, None, &Log::off());
+    sample(&mut part_one, outcome.part_one, outcome.timings.part_one)?;
+    sample(&mut part_two, outcome.part_two, outcome.timings.part_two)?;
+    parse.push(outcome.timings.parse);
+  }
+
+  Ok(DayBench {
+    year,
+    day,
+    iterations,
+    parse: Stats::from_samples(&parse),
+    part_one: part_one.map(|samples| Stats::from_samples(&samples)),
+    part_two: part_two.map(|samples| Stats::from_samples(&samples)),
+  })
+}
+
+/// Adds `duration` to the samples of a part that answered, or drops them if it is not
+/// implemented.
+fn sample(
+  samples: &mut Option<Vec<Duration>>,
+  answer: Option<Result<String>>,
+  duration: Duration,
+) -> Result<()> {
+  match answer {
+    Some(Err(Error::Unimplemented)) => *samples = None,
+    Some(Err(err)) => return Err(err),
+    _ => samples.iter_mut().for_each(|samples| samples.push(duration)),
+  }
+  Ok(())
+}
+
+pub fn save(path: &Path, results: &[DayBench]) -> io::Result<()> {
+  let json = serde_json::to_string_pretty(results)?;
+  fs::write(path, json + "\n")
+}
+
+pub fn load(path: &Path) -> io::Result<Vec<DayBench>> {
+  let json = fs::read_to_string(path)?;
+  Ok(serde_json::from_str(&json)?)
+}
+
+/// A stage whose median got slower than the baseline by more than the threshold.
+#[derive(Debug, PartialEq)]
+pub struct Regression {
+  pub year: u16,
+  pub day: u8,
+  pub stage: &'static str,
+  pub baseline_ns: u64,
+  pub current_ns: u64,
+}
+
+impl Regression {
+  /// How much slower the current median is, in percent.
+  pub fn slowdown(&self) -> f64 {
+    (self.current_ns as f64 / self.baseline_ns as f64 - 1.0) * 100.0
+  }
+}
+
+/// Compares medians of every stage present in both runs. `threshold` is in percent.
+pub fn regressions(baseline: &[DayBench], current: &[DayBench], threshold: f64) -> Vec<Regression> {
+  let mut regressions = Vec::new();
+  for curr in current {
+    let base = match baseline
+      .iter()
+      .find(|base| base.year == curr.year && base.day == curr.day)
+    {
+      Some(base) => base,
+      None => continue,
+    };
+
+    let base_stages = base.stages();
+    for (stage, curr_stats) in curr.stages() {
+      let base_stats = match base_stages.iter().find(|(base_stage, _)| *base_stage == stage) {
+        Some((_, base_stats)) => base_stats,
+        None => continue,
+      };
+      let limit = base_stats.median_ns as f64 * (1.0 + threshold / 100.0);
+      if curr_stats.median_ns as f64 > limit {
+        regressions.push(Regression {
+          year: curr.year,
+          day: curr.day,
+          stage,
+          baseline_ns: base_stats.median_ns,
+          current_ns: curr_stats.median_ns,
+        });
+      }
+    }
+  }
+  regressions
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use crate::bench::{self, DayBench, Stats};
+  use crate::error::Result;
+  use crate::log::Log;
+  use crate::problem::Problem;
+
+  fn day_bench(day: u8, median_ns: u64) -> DayBench {
+    let stats = Stats {
+      median_ns,
+      ..Stats::default()
+    };
+    DayBench {
+      year: 2020,
+      day,
+      iterations: 1,
+      parse: stats,
+      part_one: Some(stats),
+      part_two: Some(stats),
+    }
+  }
+
+  struct PartOne;
+
+  impl Problem for PartOne {
+    type Input = ();
+
+    fn new() -> Self {
+      Self
+    }
+
+    fn parse(&self, _input: &str) -> Result<()> {
+      Ok(())
+    }
+
+    fn part_one(&self, _input: &(), _log: &Log) -> Result<String> {
+      Ok("1".to_string())
+    }
+  }
+
+  #[test]
+  fn from_samples() {
+    let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_nanos).collect();
+    let stats = Stats::from_samples(&samples);
+    assert_eq!(stats.min_ns, 1);
+    assert_eq!(stats.median_ns, 10);
+    assert_eq!(stats.mean_ns, 10);
+    assert_eq!(stats.p95_ns, 19);
+  }
+
+  #[test]
+  fn regressions() {
+    let baseline = vec![day_bench(1, 100), day_bench(2, 100)];
+    let current = vec![day_bench(1, 105), day_bench(2, 150), day_bench(3, 1000)];
+
+    let regressions = bench::regressions(&baseline, &current, 10.0);
+    let stages: Vec<(u8, &str)> = regressions.iter().map(|r| (r.day, r.stage)).collect();
+    assert_eq!(stages, vec![(2, "parse"), (2, "part 1"), (2, "part 2")]);
+    assert_eq!(regressions[0].slowdown().round(), 50.0);
+
+    let mut current = day_bench(1, 1000);
+    current.part_two = None;
+    let regressions = bench::regressions(&baseline, &[current], 10.0);
+    let stages: Vec<&str> = regressions.iter().map(|r| r.stage).collect();
+    assert_eq!(stages, vec!["parse", "part 1"]);
+  }
+
+  #[test]
+  fn skips_unimplemented_parts() {
+    let result = bench::bench(&PartOne, "", 2020, 1, 3).unwrap();
+    assert!(result.part_one.is_some());
+    assert!(result.part_two.is_none());
+    let stages: Vec<&str> = result.stages().iter().map(|&(stage, _)| stage).collect();
+    assert_eq!(stages, vec!["parse", "part 1"]);
+  }
+}
@@ -1,96 +1,540 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::str::FromStr;
 
+use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::parse;
 use crate::problem::Problem;
 
-#[derive(Default)]
-pub struct DayOne {}
+/// The puzzle's target sum.
+pub const TARGET: i32 = 2020;
+
+/// The indices, in increasing order, of the first `k` numbers in `nums` that add up to `target`.
+pub fn k_sum(nums: &[i32], k: usize, target: i32) -> Option<Vec<usize>> {
+  k_sums(nums, k, target).next()
+}
+
+/// Every combination of `k` distinct indices into `nums` whose numbers add up to `target`, each
+/// in increasing order, in lexicographic order. Repeated numbers count once per index, so
+/// `[1010, 1010]` has a 2-sum to 2020 but `[1010]` does not.
+pub fn k_sums(nums: &[i32], k: usize, target: i32) -> KSums<'_> {
+  let mut positions: HashMap<i32, Vec<usize>> = HashMap::with_capacity(nums.len());
+  for (idx, &num) in nums.iter().enumerate() {
+    positions.entry(num).or_default().push(idx);
+  }
+
+  let mut pending = VecDeque::new();
+  if k == 0 && target == 0 {
+    pending.push_back(Vec::new());
+  }
+  KSums {
+    nums,
+    target: target as i64,
+    positions,
+    prefix: if k > 0 && k <= nums.len() { Some((0..k - 1).collect()) } else { None },
+    pending,
+  }
+}
+
+/// Iterator returned by `k_sums`.
+pub struct KSums<'a> {
+  nums: &'a [i32],
+  target: i64,
+  /// Number -> every index it appears at, in increasing order.
+  positions: HashMap<i32, Vec<usize>>,
+  /// The first k - 1 indices of the next combinations to look for, or None once all have been.
+  /// The last index is looked up in `positions`.
+  prefix: Option<Vec<usize>>,
+  /// Combinations found but not returned yet.
+  pending: VecDeque<Vec<usize>>,
+}
 
-impl DayOne {
-  /// Returns the indices of the two numbers in `nums` that add up to `target`
-  fn two_sum(nums: &Vec<i32>, target: i32) -> Option<(usize, usize)> {
-    let mut complements = HashMap::with_capacity(nums.len());
-    for (idx, num) in nums.iter().enumerate() {
-      let complement = target - num;
-      if let Some(c_idx) = complements.get(&complement) {
-        return Some((idx, *c_idx));
+impl KSums<'_> {
+  /// Moves on to the prefix after the current one, if any.
+  fn advance(&mut self) {
+    // Prefixes leave the last index of `nums` for the final number.
+    let n = self.nums.len() - 1;
+    let prefix = match &mut self.prefix {
+      Some(prefix) => prefix,
+      None => return,
+    };
+    let m = prefix.len();
+    // The rightmost index that can still move right, leaving room for the ones after it.
+    match (0..m).rev().find(|&i| prefix[i] < n - m + i) {
+      Some(i) => {
+        prefix[i] += 1;
+        for j in i + 1..m {
+          prefix[j] = prefix[j - 1] + 1;
+        }
       }
-      complements.insert(num, idx);
+      None => self.prefix = None,
     }
-    // No result found (should not happen)
-    None
   }
 
-  /// Returns the indices of the three numbers in `nums` that add up to `target`
-  fn three_sum(nums: &Vec<i32>, target: i32) -> Option<(usize, usize, usize)> {
-    // Calculate two_sum around a pivot
-    let two_sum_x = |pivot_idx: usize, target_x: i32| -> Option<(usize, usize)> {
-      let mut complements = HashMap::with_capacity(nums.len());
-      for (idx, num) in nums.iter().enumerate() {
-        if idx == pivot_idx {
-          // Skip the pivot element
-          continue;
+  /// Queues every combination that starts with `prefix`.
+  fn complete(&mut self, prefix: &[usize]) {
+    let sum: i64 = prefix.iter().map(|&idx| self.nums[idx] as i64).sum();
+    let last = match i32::try_from(self.target - sum) {
+      Ok(last) => last,
+      Err(_) => return,
+    };
+    let positions = match self.positions.get(&last) {
+      Some(positions) => positions,
+      None => return,
+    };
+
+    // Only indices after the prefix keep the combination distinct and in increasing order.
+    let after = prefix.last().map_or(0, |&idx| idx + 1);
+    let start = positions.partition_point(|&idx| idx < after);
+    for &idx in &positions[start..] {
+      let mut indices = prefix.to_vec();
+      indices.push(idx);
+      self.pending.push_back(indices);
+    }
+  }
+}
+
+impl Iterator for KSums<'_> {
+  type Item = Vec<usize>;
+
+  fn next(&mut self) -> Option<Vec<usize>> {
+    loop {
+      if let Some(indices) = self.pending.pop_front() {
+        return Some(indices);
+      }
+      let prefix = self.prefix.clone()?;
+      self.advance();
+      self.complete(&prefix);
+    }
+  }
+}
+
+/// Strategies for finding one k-sum, to compare against each other. They all return distinct
+/// indices in increasing order, but may pick different combinations when there are several.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algo {
+  /// Enumerates the first k - 1 indices and looks the last number up by value, see `k_sums`.
+  Hash,
+  /// Sorts the numbers, then fixes the first k - 2 and closes in on the last two from both ends.
+  TwoPointer,
+  /// Like `Hash`, but tests for the last number in a bitset over the range of the numbers and
+  /// finds its indices in a table indexed by value. Falls back to `Hash` if that range is too
+  /// wide.
+  Bitset,
+  /// Tries every combination.
+  BruteForce,
+}
+
+impl Algo {
+  pub const ALL: [Algo; 4] = [Algo::Hash, Algo::TwoPointer, Algo::Bitset, Algo::BruteForce];
+
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Algo::Hash => "hash",
+      Algo::TwoPointer => "two-pointer",
+      Algo::Bitset => "bitset",
+      Algo::BruteForce => "brute-force",
+    }
+  }
+
+  /// Why the algorithm cannot search `nums`, if it cannot. `k_sum` finds nothing then.
+  pub fn skip_reason(self, nums: &[i32]) -> Option<&'static str> {
+    match (self, nums.iter().min(), nums.iter().max()) {
+      (Algo::Bitset, Some(&min), Some(&max)) if max as i64 - min as i64 >= BITSET_RANGE => {
+        Some("range too wide")
+      }
+      _ => None,
+    }
+  }
+
+  /// The indices of `k` numbers in `nums` that add up to `target`.
+  pub fn k_sum(self, nums: &[i32], k: usize, target: i32) -> Option<Vec<usize>> {
+    // The searches below would otherwise try every subset before giving up.
+    if k > nums.len() {
+      return None;
+    }
+    match self {
+      Algo::Hash => k_sum(nums, k, target),
+      Algo::TwoPointer => two_pointer(nums, k, target),
+      Algo::Bitset => bitset(nums, k, target),
+      Algo::BruteForce => brute_force(nums, k, target),
+    }
+  }
+}
+
+impl FromStr for Algo {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, String> {
+    Algo::ALL
+      .iter()
+      .copied()
+      .find(|algo| algo.as_str() == s)
+      .ok_or_else(|| format!("Unknown algorithm {:?}", s))
+  }
+}
+
+fn two_pointer(nums: &[i32], k: usize, target: i32) -> Option<Vec<usize>> {
+  /// Picks `k` of `sorted` adding up to `target` into `picked`.
+  fn pick(sorted: &[(i64, usize)], k: usize, target: i64, picked: &mut Vec<usize>) -> bool {
+    match k {
+      0 => target == 0,
+      1 => match sorted.binary_search_by_key(&target, |&(num, _)| num) {
+        Ok(pos) => {
+          picked.push(sorted[pos].1);
+          true
         }
-        let complement = target_x - num;
-        if let Some(c_idx) = complements.get(&complement) {
-          return Some((idx, *c_idx));
+        Err(_) => false,
+      },
+      2 => {
+        let (mut lo, mut hi) = (0, sorted.len());
+        while lo + 1 < hi {
+          let sum = sorted[lo].0 + sorted[hi - 1].0;
+          if sum == target {
+            picked.extend([sorted[lo].1, sorted[hi - 1].1]);
+            return true;
+          } else if sum < target {
+            lo += 1;
+          } else {
+            hi -= 1;
+          }
         }
-        complements.insert(num, idx);
+        false
       }
-      None
-    };
+      _ => {
+        for (pos, &(num, idx)) in sorted.iter().enumerate() {
+          // A repeated number finds nothing the first of its kind did not.
+          if pos > 0 && sorted[pos - 1].0 == num {
+            continue;
+          }
+          picked.push(idx);
+          if pick(&sorted[pos + 1..], k - 1, target - num, picked) {
+            return true;
+          }
+          picked.pop();
+        }
+        false
+      }
+    }
+  }
+
+  let mut sorted: Vec<(i64, usize)> = nums.iter().map(|&num| num as i64).zip(0..).collect();
+  sorted.sort_unstable();
+  let mut picked = Vec::with_capacity(k);
+  if pick(&sorted, k, target as i64, &mut picked) {
+    picked.sort_unstable();
+    Some(picked)
+  } else {
+    None
+  }
+}
+
+/// The widest range of numbers `Algo::Bitset` allocates its tables for.
+const BITSET_RANGE: i64 = 1 << 20;
+
+/// The numbers of `Algo::Bitset` by value: which are present, and the indices of each.
+struct Bitset {
+  min: i64,
+  bits: Vec<u64>,
+  /// The indices of value `min + v` are `indices[starts[v]..starts[v + 1]]`, in increasing order.
+  starts: Vec<usize>,
+  indices: Vec<usize>,
+}
+
+impl Bitset {
+  fn new(nums: &[i32], min: i64, max: i64) -> Self {
+    let range = (max - min + 1) as usize;
+    let mut bits = vec![0u64; range / 64 + 1];
+    let mut starts = vec![0; range + 1];
+    for &num in nums {
+      let bit = (num as i64 - min) as usize;
+      bits[bit / 64] |= 1 << (bit % 64);
+      starts[bit + 1] += 1;
+    }
+    for bit in 0..range {
+      starts[bit + 1] += starts[bit];
+    }
+    let mut next = starts.clone();
+    let mut indices = vec![0; nums.len()];
+    for (idx, &num) in nums.iter().enumerate() {
+      let bit = (num as i64 - min) as usize;
+      indices[next[bit]] = idx;
+      next[bit] += 1;
+    }
+    Self {
+      min,
+      bits,
+      starts,
+      indices,
+    }
+  }
+
+  /// The first index after `start` holding `num`.
+  fn find(&self, num: i64, start: usize) -> Option<usize> {
+    let bit = usize::try_from(num - self.min).ok()?;
+    if bit >= self.starts.len() - 1 || self.bits[bit / 64] & (1 << (bit % 64)) == 0 {
+      return None;
+    }
+    let indices = &self.indices[self.starts[bit]..self.starts[bit + 1]];
+    let pos = indices.partition_point(|&idx| idx < start);
+    indices.get(pos).copied()
+  }
+}
+
+fn bitset(nums: &[i32], k: usize, target: i32) -> Option<Vec<usize>> {
+  let (min, max) = match (nums.iter().min(), nums.iter().max()) {
+    (Some(&min), Some(&max)) if k > 0 => (min as i64, max as i64),
+    _ => return k_sum(nums, k, target),
+  };
+  if max - min >= BITSET_RANGE {
+    return None;
+  }
+  let bitset = Bitset::new(nums, min, max);
 
-    for (pivot_idx, pivot) in nums.iter().enumerate() {
-      if let Some(tuple) = two_sum_x(pivot_idx, target - pivot) {
-        return Some((pivot_idx, tuple.0, tuple.1));
+  /// Extends `picked` with increasing indices until it holds k - 1, then looks up the last.
+  fn pick(
+    nums: &[i32],
+    k: usize,
+    remaining: i64,
+    bitset: &Bitset,
+    picked: &mut Vec<usize>,
+  ) -> bool {
+    let start = picked.last().map_or(0, |&idx| idx + 1);
+    if picked.len() == k - 1 {
+      return match bitset.find(remaining, start) {
+        Some(idx) => {
+          picked.push(idx);
+          true
+        }
+        None => false,
+      };
+    }
+    for idx in start..nums.len() {
+      picked.push(idx);
+      if pick(nums, k, remaining - nums[idx] as i64, bitset, picked) {
+        return true;
       }
+      picked.pop();
     }
+    false
+  }
 
-    // No result found (should not happen)
+  let mut picked = Vec::with_capacity(k);
+  if pick(nums, k, target as i64, &bitset, &mut picked) {
+    Some(picked)
+  } else {
     None
   }
 }
 
+fn brute_force(nums: &[i32], k: usize, target: i32) -> Option<Vec<usize>> {
+  fn pick(nums: &[i32], k: usize, remaining: i64, picked: &mut Vec<usize>) -> bool {
+    if picked.len() == k {
+      return remaining == 0;
+    }
+    let start = picked.last().map_or(0, |&idx| idx + 1);
+    for idx in start..nums.len() {
+      picked.push(idx);
+      if pick(nums, k, remaining - nums[idx] as i64, picked) {
+        return true;
+      }
+      picked.pop();
+    }
+    false
+  }
+
+  let mut picked = Vec::with_capacity(k);
+  if pick(nums, k, target as i64, &mut picked) {
+    Some(picked)
+  } else {
+    None
+  }
+}
+
+/// `len` pseudo-random numbers in `1..=max` from a linear congruential generator, to benchmark
+/// the algorithms on inputs larger than the puzzle's. The same seed gives the same numbers.
+pub fn generate(len: usize, max: i32, seed: u64) -> Vec<i32> {
+  let mut state = seed;
+  (0..len)
+    .map(|_| {
+      // Constants from Knuth's MMIX.
+      state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      ((state >> 33) % max.max(1) as u64) as i32 + 1
+    })
+    .collect()
+}
+
+/// The product of the numbers at `indices`, or None if it overflows an `i64`.
+pub fn product(nums: &[i32], indices: &[usize]) -> Option<i64> {
+  indices
+    .iter()
+    .try_fold(1i64, |product, &idx| product.checked_mul(nums[idx] as i64))
+}
+
+#[derive(Default)]
+pub struct DayOne {}
+
+crate::register!(2020, 1, DayOne);
+
 impl Problem for DayOne {
+  type Input = Vec<i32>;
+
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(646779.to_string())
+  fn parse(&self, input: &str) -> Result<Vec<i32>> {
+    parse::numbers(input)
+  }
+
+  fn part_one(&self, nums: &Vec<i32>, _log: &Log) -> Result<String> {
+    let indices = k_sum(nums, 2, TARGET).ok_or(Error::NoSolution)?;
+    let product = product(nums, &indices).ok_or(Error::NoSolution)?;
+    Ok(product.to_string())
+  }
+
+  fn part_two(&self, nums: &Vec<i32>, _log: &Log) -> Result<String> {
+    let indices = k_sum(nums, 3, TARGET).ok_or(Error::NoSolution)?;
+    let product = product(nums, &indices).ok_or(Error::NoSolution)?;
+    Ok(product.to_string())
   }
+}
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let target = 2020;
-    let nums: Vec<i32> = input
-      .split('\n')
-      .map(|s| s.parse())
-      .filter_map(Result::ok)
-      .collect();
+#[cfg(test)]
+mod tests {
+  use proptest::prelude::*;
+
+  use crate::day01::{generate, k_sum, k_sums, product, Algo};
+
+  /// Every increasing combination of `k` indices into `nums` that adds up to `target`, the slow
+  /// way.
+  fn oracle(nums: &[i32], k: usize, target: i64) -> Vec<Vec<usize>> {
+    fn pick(
+      nums: &[i32],
+      k: usize,
+      target: i64,
+      picked: &mut Vec<usize>,
+      out: &mut Vec<Vec<usize>>,
+    ) {
+      if picked.len() == k {
+        if picked.iter().map(|&idx| nums[idx] as i64).sum::<i64>() == target {
+          out.push(picked.clone());
+        }
+        return;
+      }
+      let start = picked.last().map_or(0, |&idx| idx + 1);
+      for idx in start..nums.len() {
+        picked.push(idx);
+        pick(nums, k, target, picked, out);
+        picked.pop();
+      }
+    }
 
-    let indices = Self::two_sum(&nums, target);
-    indices
-      .map(|(i, j)| nums[i] * nums[j])
-      .map(|val| val.to_string())
+    let mut out = Vec::new();
+    pick(nums, k, target, &mut Vec::new(), &mut out);
+    out
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some(246191688.to_string())
+  proptest! {
+    #[test]
+    fn k_sums_match_oracle(
+      nums in prop::collection::vec(-20i32..20, 0..12),
+      k in 0usize..5,
+      target in -40i32..40,
+    ) {
+      let all: Vec<Vec<usize>> = k_sums(&nums, k, target).collect();
+      prop_assert_eq!(&all, &oracle(&nums, k, target as i64));
+      prop_assert_eq!(k_sum(&nums, k, target), all.first().cloned());
+    }
+
+    #[test]
+    fn algos_find_a_solution(
+      nums in prop::collection::vec(-20i32..20, 0..12),
+      k in 0usize..14,
+      target in -40i32..40,
+    ) {
+      let all = oracle(&nums, k, target as i64);
+      for algo in Algo::ALL.iter() {
+        match algo.k_sum(&nums, k, target) {
+          Some(indices) => prop_assert!(all.contains(&indices), "{:?} found {:?}", algo, indices),
+          None => prop_assert!(all.is_empty(), "{:?} found nothing", algo),
+        }
+      }
+    }
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let target = 2020;
-    let nums: Vec<i32> = input
-      .split('\n')
-      .map(|s| s.parse())
-      .filter_map(Result::ok)
-      .collect();
+  #[test]
+  fn algos() {
+    let nums = generate(300, 5000, 7);
+    for k in 1..=3 {
+      let target = nums[10..10 + k].iter().sum();
+      for algo in Algo::ALL.iter() {
+        let indices = algo.k_sum(&nums, k, target).unwrap();
+        let sum: i32 = indices.iter().map(|&idx| nums[idx]).sum();
+        assert_eq!(sum, target, "{:?}", algo);
+      }
+    }
+    for algo in Algo::ALL.iter() {
+      assert_eq!(algo.k_sum(&nums, nums.len() + 1, 2020), None, "{:?}", algo);
+      assert_eq!(algo.skip_reason(&nums), None, "{:?}", algo);
+    }
 
-    let indices = Self::three_sum(&nums, target);
-    indices
-      .map(|(i, j, k)| nums[i] * nums[j] * nums[k])
-      .map(|val| val.to_string())
+    let wide = [0, 1 << 20, 5];
+    assert_eq!(Algo::Bitset.skip_reason(&wide), Some("range too wide"));
+    assert_eq!(Algo::Bitset.k_sum(&wide, 2, 5), None);
+    assert_eq!(Algo::Hash.skip_reason(&wide), None);
+    assert_eq!("two-pointer".parse(), Ok(Algo::TwoPointer));
+    assert!("fast".parse::<Algo>().is_err());
   }
-}
 
-#[cfg(test)]
-mod tests {}
+  #[test]
+  fn large_product() {
+    let nums = generate(40, 2020, 1);
+    let indices = k_sum(&nums, 7, 9000).unwrap();
+    assert_eq!(product(&nums, &indices), None);
+    let pair = nums[indices[0]] as i64 * nums[indices[1]] as i64;
+    assert_eq!(product(&nums, &indices[..2]), Some(pair));
+    assert_eq!(product(&nums, &[]), Some(1));
+  }
+
+  #[test]
+  fn generated() {
+    let nums = generate(1000, 2020, 1);
+    assert_eq!(nums, generate(1000, 2020, 1));
+    assert_ne!(nums, generate(1000, 2020, 2));
+    assert!(nums.iter().all(|num| (1..=2020).contains(num)));
+  }
+
+  #[test]
+  fn distinct_indices() {
+    // A single 1010 cannot pair with itself, but two can.
+    assert_eq!(k_sum(&[1010, 3], 2, 2020), None);
+    assert_eq!(k_sum(&[1010, 3, 1010], 2, 2020), Some(vec![0, 2]));
+    assert_eq!(k_sum(&[1000, 10, 1000], 3, 2010), Some(vec![0, 1, 2]));
+    assert_eq!(k_sum(&[1010, 5], 3, 2025), None);
+    assert_eq!(k_sums(&[5, 5, 5], 2, 10).count(), 3);
+    // Partial sums beyond i32 do not overflow.
+    assert_eq!(k_sum(&[i32::MAX, i32::MAX, 2], 2, i32::MAX), None);
+  }
+
+  #[test]
+  fn k_sum_given() {
+    let nums = [1721, 979, 366, 299, 675, 1456];
+    assert_eq!(k_sum(&nums, 2, 2020), Some(vec![0, 3]));
+    assert_eq!(k_sum(&nums, 3, 2020), Some(vec![1, 2, 4]));
+    assert_eq!(k_sum(&nums, 4, 2020), None);
+  }
+
+  #[test]
+  fn k_sums_all() {
+    let nums = [1, 2, 3, 4, 5];
+    let all: Vec<Vec<usize>> = k_sums(&nums, 2, 6).collect();
+    assert_eq!(all, vec![vec![0, 4], vec![1, 3]]);
+    assert_eq!(k_sums(&nums, 5, 15).count(), 1);
+    assert_eq!(k_sums(&nums, 6, 15).count(), 0);
+    assert_eq!(k_sums(&nums, 0, 0).collect::<Vec<_>>(), vec![Vec::<usize>::new()]);
+  }
+}
@@ -1,74 +1,221 @@
+use crate::error::Result;
+use crate::log::Log;
+use crate::parse::{self, Line};
 use crate::problem::Problem;
+use crate::validation::{Check, Verdict};
 
 use lazy_static::lazy_static;
 use regex::Regex;
 
 lazy_static! {
   static ref LINE_PATTERN: Regex =
-    Regex::new(r"^(?P<min>\d+)-(?P<max>\d+) (?P<letter>[a-z]): (?P<pass>[a-z]+)$").unwrap();
+    Regex::new(r"^(?P<low>\d+)-(?P<high>\d+) (?P<letter>[a-z]): (?P<pass>[a-z]+)$").unwrap();
+}
+
+/// The `low-high letter` part of a line. What it demands of the password depends on the `Policy`
+/// it is checked against.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LetterPolicy {
+  pub low: usize,
+  pub high: usize,
+  pub letter: char,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordEntry {
+  pub policy: LetterPolicy,
+  pub password: String,
+  /// The 1-based line the entry was read from.
+  pub line: usize,
+}
+
+/// A rule a password entry must follow.
+pub trait Policy: Send + Sync {
+  /// Ok, or which part of the rule `entry` breaks.
+  fn check(&self, entry: &PasswordEntry) -> Check;
+
+  fn is_valid(&self, entry: &PasswordEntry) -> bool {
+    self.check(entry).is_ok()
+  }
+}
+
+/// Part one: the letter appears between `low` and `high` times, inclusive.
+pub struct CountRange;
+
+impl Policy for CountRange {
+  fn check(&self, entry: &PasswordEntry) -> Check {
+    let LetterPolicy { low, high, letter } = entry.policy;
+    let count = entry.password.matches(letter).count();
+    if count < low {
+      Err(format!("letter {:?} appears {} times, min {}", letter, count, low))
+    } else if count > high {
+      Err(format!("letter {:?} appears {} times, max {}", letter, count, high))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/// Part two: the letter is at exactly one of the 1-based positions `low` and `high`.
+pub struct Positions;
+
+impl Policy for Positions {
+  fn check(&self, entry: &PasswordEntry) -> Check {
+    let LetterPolicy { low, high, letter } = entry.policy;
+    // Positions are 1-based, so position 0 never holds the letter.
+    let is_letter_at = |pos: usize| {
+      pos
+        .checked_sub(1)
+        .and_then(|idx| entry.password.chars().nth(idx))
+        == Some(letter)
+    };
+    match (is_letter_at(low), is_letter_at(high)) {
+      (true, true) => Err(format!("letter {:?} at both {} and {}", letter, low, high)),
+      (false, false) => Err(format!("letter {:?} at neither {} nor {}", letter, low, high)),
+      _ => Ok(()),
+    }
+  }
+}
+
+/// The password has at least this many different letters. Ignores the entry's own policy.
+pub struct DistinctLetters(pub usize);
+
+impl Policy for DistinctLetters {
+  fn check(&self, entry: &PasswordEntry) -> Check {
+    let mut letters: Vec<char> = entry.password.chars().collect();
+    letters.sort_unstable();
+    letters.dedup();
+    if letters.len() < self.0 {
+      return Err(format!("{} distinct letters, min {}", letters.len(), self.0));
+    }
+    Ok(())
+  }
+}
+
+/// No letter of the password repeats more than this many times in a row. Ignores the entry's own
+/// policy.
+pub struct MaxRun(pub usize);
+
+impl Policy for MaxRun {
+  fn check(&self, entry: &PasswordEntry) -> Check {
+    let chars: Vec<char> = entry.password.chars().collect();
+    match chars.chunk_by(|a, b| a == b).find(|run| run.len() > self.0) {
+      Some(run) => Err(format!(
+        "letter {:?} repeats {} times in a row, max {}",
+        run[0],
+        run.len(),
+        self.0
+      )),
+      None => Ok(()),
+    }
+  }
 }
 
 #[derive(Default)]
 pub struct DayTwo {}
 
 impl DayTwo {
-  fn is_valid_password_p1(line: &str) -> bool {
-    let caps = LINE_PATTERN.captures(line).unwrap();
-    let min: usize = caps["min"].parse().unwrap();
-    let max: usize = caps["max"].parse().unwrap();
-    let letter: char = caps["letter"].parse().unwrap();
-    let password = &caps["pass"];
+  fn parse_line(line: Line) -> Result<PasswordEntry> {
+    let caps = parse::captures(&LINE_PATTERN, line, "policy")?;
 
-    let count = password.matches(letter).count();
-    count >= min && count <= max
+    Ok(PasswordEntry {
+      policy: LetterPolicy {
+        low: parse::capture(&caps, line, "low")?,
+        high: parse::capture(&caps, line, "high")?,
+        letter: parse::capture(&caps, line, "letter")?,
+      },
+      password: caps["pass"].to_string(),
+      line: line.num,
+    })
   }
 
-  fn is_valid_password_p2(line: &str) -> bool {
-    let caps = LINE_PATTERN.captures(line).unwrap();
-    let min: usize = caps["min"].parse().unwrap();
-    let max: usize = caps["max"].parse().unwrap();
-    let letter: char = caps["letter"].parse().unwrap();
-    let password = &caps["pass"];
+  /// How many of `entries` follow `policy`.
+  pub fn count_valid(entries: &[PasswordEntry], policy: &dyn Policy) -> usize {
+    entries.iter().filter(|entry| policy.is_valid(entry)).count()
+  }
 
-    (password.chars().nth(min - 1).unwrap() == letter)
-      ^ (password.chars().nth(max - 1).unwrap() == letter)
+  /// The policy of `part`.
+  fn policy(part: u8) -> &'static dyn Policy {
+    if part == 1 {
+      &CountRange
+    } else {
+      &Positions
+    }
   }
 }
 
+crate::register!(2020, 2, DayTwo);
+
 impl Problem for DayTwo {
+  type Input = Vec<PasswordEntry>;
+
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(628.to_string())
+  fn parse(&self, input: &str) -> Result<Vec<PasswordEntry>> {
+    parse::map_lines(input, Self::parse_line)
+  }
+
+  fn part_one(&self, entries: &Vec<PasswordEntry>, _log: &Log) -> Result<String> {
+    Ok(Self::count_valid(entries, Self::policy(1)).to_string())
   }
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let lines: Vec<&str> = input.split('\n').collect();
+  fn part_two(&self, entries: &Vec<PasswordEntry>, _log: &Log) -> Result<String> {
+    Ok(Self::count_valid(entries, Self::policy(2)).to_string())
+  }
 
-    let count: usize = lines
+  fn validate(&self, entries: &Vec<PasswordEntry>, part: u8) -> Result<Vec<Verdict>> {
+    let policy = Self::policy(part);
+    let verdicts = entries
       .iter()
-      .filter(|line| !line.is_empty())
-      .map(|line| DayTwo::is_valid_password_p1(line))
-      .filter(|&validity| validity == true)
-      .count();
-    Some(count.to_string())
+      .map(|entry| Verdict::new(entry.line, entry.line, policy.check(entry)))
+      .collect();
+    Ok(verdicts)
   }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::day02::{CountRange, DayTwo, DistinctLetters, MaxRun, PasswordEntry, Policy, Positions};
+  use crate::problem::Problem;
 
-  fn soln_two(&self) -> Option<String> {
-    Some(705.to_string())
+  fn entries() -> Vec<PasswordEntry> {
+    DayTwo::new()
+      .parse("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n")
+      .unwrap()
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let lines: Vec<&str> = input.split('\n').collect();
+  #[test]
+  fn policies() {
+    let entries = entries();
+    let verdicts = |policy: &dyn Policy| -> Vec<bool> {
+      entries.iter().map(|entry| policy.is_valid(entry)).collect()
+    };
+    assert_eq!(verdicts(&CountRange), vec![true, false, true]);
+    assert_eq!(verdicts(&Positions), vec![true, false, false]);
+    assert_eq!(verdicts(&DistinctLetters(5)), vec![true, true, false]);
+    assert_eq!(verdicts(&MaxRun(8)), vec![true, true, false]);
+    assert_eq!(DayTwo::count_valid(&entries, &MaxRun(9)), 3);
+  }
 
-    let count: usize = lines
-      .iter()
-      .filter(|line| !line.is_empty())
-      .map(|line| DayTwo::is_valid_password_p2(line))
-      .filter(|&validity| validity == true)
-      .count();
-    Some(count.to_string())
+  #[test]
+  fn reasons() {
+    let entries = entries();
+    assert_eq!(
+      CountRange.check(&entries[1]),
+      Err("letter 'b' appears 0 times, min 1".to_string())
+    );
+    assert_eq!(
+      Positions.check(&entries[2]),
+      Err("letter 'c' at both 2 and 9".to_string())
+    );
+    assert_eq!(
+      MaxRun(3).check(&entries[2]),
+      Err("letter 'c' repeats 9 times in a row, max 3".to_string())
+    );
+    let verdicts = DayTwo::new().validate(&entries, 1).unwrap();
+    assert_eq!(verdicts[1].first_line, 2);
+    assert!(verdicts[2].valid);
   }
 }
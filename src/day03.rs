@@ -1,82 +1,59 @@
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::log::Log;
 use crate::problem::Problem;
 
 #[derive(Default)]
-pub struct DayThree {
-  debug: bool,
-}
+pub struct DayThree {}
 
 impl DayThree {
-  fn count_trees(&self, map: &Vec<Vec<char>>, slope: (usize, usize)) -> u32 {
+  fn count_trees(&self, map: &Grid<char>, slope: (usize, usize), log: &Log) -> u32 {
     const TREE: char = '#';
-    let width = map[0].len();
-    let height = map.len();
 
     let mut num_trees = 0;
-    let mut curr_x = slope.0;
-    let mut curr_y = slope.1;
-
-    while curr_y < height {
-      // analyze obj at position
-      let obj = map[curr_y][curr_x];
+    // The map repeats to the right, so only reaching the bottom ends the descent.
+    for step in 1.. {
+      let pos = (step * slope.0, step * slope.1);
+      let obj = match map.get_wrapping(pos) {
+        Some(obj) => *obj,
+        None => break,
+      };
       if obj == TREE {
-        if self.debug {
-          println!("Found tree at ({}, {})", curr_x, curr_y);
-        }
+        log.trace(format_args!("Found tree at ({}, {})", pos.0 % map.width(), pos.1));
         num_trees += 1;
       }
-
-      // move
-      curr_x += slope.0;
-      curr_y += slope.1;
-
-      // correct curr_x
-      curr_x %= width;
     }
 
+    log.debug(format_args!("Slope {:?} hits {} trees", slope, num_trees));
     num_trees
   }
 }
 
+crate::register!(2020, 3, DayThree);
+
 impl Problem for DayThree {
-  fn new() -> Self {
-    Self { debug: false }
-  }
+  type Input = Grid<char>;
 
-  fn debug() -> Self {
-    Self { debug: true }
+  fn new() -> Self {
+    Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(268.to_string())
+  fn parse(&self, input: &str) -> Result<Grid<char>> {
+    Grid::parse(input)
   }
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let map: Vec<Vec<char>> = input
-      .split('\n')
-      .filter(|line| !line.is_empty())
-      .map(|line| line.chars().collect())
-      .collect();
-
+  fn part_one(&self, map: &Grid<char>, log: &Log) -> Result<String> {
     const SLOPE: (usize, usize) = (3, 1);
-    Some(self.count_trees(&map, SLOPE).to_string())
-  }
-
-  fn soln_two(&self) -> Option<String> {
-    Some(3093068400_u32.to_string())
+    Ok(self.count_trees(map, SLOPE, log).to_string())
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let map: Vec<Vec<char>> = input
-      .split('\n')
-      .filter(|line| !line.is_empty())
-      .map(|line| line.chars().collect())
-      .collect();
-    let slopes = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+  fn part_two(&self, map: &Grid<char>, log: &Log) -> Result<String> {
+    let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
     let result = slopes
       .iter()
-      .map(|&slope| self.count_trees(&map, slope))
-      .fold(1, |a, b| a * b);
+      .map(|&slope| self.count_trees(map, slope, log))
+      .product::<u32>();
 
-    Some(result.to_string())
+    Ok(result.to_string())
   }
 }
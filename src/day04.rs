@@ -1,197 +1,617 @@
+use crate::error::Result;
+use crate::log::Log;
+use crate::parse;
 use crate::problem::Problem;
+use crate::schema::Schema;
+use crate::validation::{Check, Verdict};
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 
-use lazy_static::lazy_static;
-use regex::Regex;
+use serde::Serialize;
 
-const KNOWN_FIELDS: usize = 8;
+/// A `key:value` pair of a passport, with the 1-based line it was found on.
+#[derive(Clone, Debug)]
+pub struct Field {
+  key: String,
+  value: String,
+  line: usize,
+}
 
-lazy_static! {
-  static ref HEIGHT: Regex = Regex::new(r"^(?P<num>\d+)(?P<unit>(in|cm)?)$").unwrap();
-  static ref HAIR_COLOR: Regex = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
-  static ref PASSPORT_ID: Regex = Regex::new(r"^\d{9}$").unwrap();
-  static ref EYE_COLORS: HashSet<&'static str> = {
-    let mut set = HashSet::new();
-    set.insert("amb");
-    set.insert("blu");
-    set.insert("brn");
-    set.insert("gry");
-    set.insert("grn");
-    set.insert("hzl");
-    set.insert("oth");
-    set
-  };
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Height {
+  Cm(u32),
+  In(u32),
 }
 
-#[derive(Default)]
-pub struct DayFour {}
+impl FromStr for Height {
+  type Err = ();
 
-impl DayFour {
-  fn is_valid_p1(&self, lines: &str) -> bool {
-    let mut fields: HashSet<&str> = HashSet::new();
-
-    for line in lines.split('\n') {
-      for detail in line.split(' ') {
-        let field = detail.split(':').nth(0).unwrap();
-        let new_val = fields.insert(field);
-        if !new_val {
-          // Two entries for the same field!?
-          return false;
-        }
-      }
+  fn from_str(s: &str) -> std::result::Result<Self, ()> {
+    let digits = s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let num = s[..digits].parse().map_err(|_| ())?;
+    match &s[digits..] {
+      "cm" => Ok(Height::Cm(num)),
+      "in" => Ok(Height::In(num)),
+      _ => Err(()),
     }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct HairColor {
+  pub r: u8,
+  pub g: u8,
+  pub b: u8,
+}
 
-    fields.len() == KNOWN_FIELDS || (fields.len() == KNOWN_FIELDS - 1 && !fields.contains("cid"))
+impl FromStr for HairColor {
+  type Err = ();
+
+  /// Parses `#rrggbb` in lowercase hex, the only form the puzzle accepts.
+  fn from_str(s: &str) -> std::result::Result<Self, ()> {
+    let hex = s.strip_prefix('#').ok_or(())?;
+    if hex.len() != 6 || !hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+      return Err(());
+    }
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|_| ());
+    Ok(HairColor {
+      r: channel(0)?,
+      g: channel(2)?,
+      b: channel(4)?,
+    })
   }
+}
 
-  fn is_valid_p2(&self, lines: &str) -> bool {
-    let mut fields: HashSet<&str> = HashSet::new();
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EyeColor {
+  Amb,
+  Blu,
+  Brn,
+  Gry,
+  Grn,
+  Hzl,
+  Oth,
+}
 
-    for line in lines.split('\n') {
-      if line.is_empty() {
-        continue;
-      }
-      for detail in line.split(' ') {
-        let mut pairs = detail.split(':');
-        let field = pairs.next().expect(&format!("No field for {}", detail));
-        let value = pairs.next().expect(&format!("No value for {}", detail));
+impl FromStr for EyeColor {
+  type Err = ();
 
-        if !Self::is_valid_pair(field, value) {
-          return false;
-        }
+  fn from_str(s: &str) -> std::result::Result<Self, ()> {
+    match s {
+      "amb" => Ok(EyeColor::Amb),
+      "blu" => Ok(EyeColor::Blu),
+      "brn" => Ok(EyeColor::Brn),
+      "gry" => Ok(EyeColor::Gry),
+      "grn" => Ok(EyeColor::Grn),
+      "hzl" => Ok(EyeColor::Hzl),
+      "oth" => Ok(EyeColor::Oth),
+      _ => Err(()),
+    }
+  }
+}
 
-        let new_val = fields.insert(field);
-        if !new_val {
-          // Two entries for the same field!?
-          return false;
-        }
-      }
+/// A field of a `Passport`: missing, typed, or with a value of the wrong shape. In JSON these are
+/// `null`, the typed value and `{"malformed": "<value>"}`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Value<T> {
+  #[default]
+  Missing,
+  Typed(T),
+  Malformed { malformed: String },
+}
+
+impl<T> Value<T> {
+  /// `typed`, or `raw` as malformed if it could not be typed.
+  fn new(raw: &str, typed: Option<T>) -> Self {
+    match typed {
+      Some(typed) => Value::Typed(typed),
+      None => Value::Malformed {
+        malformed: raw.to_string(),
+      },
     }
+  }
 
-    fields.len() == KNOWN_FIELDS || (fields.len() == KNOWN_FIELDS - 1 && !fields.contains("cid"))
+  fn is_missing(&self) -> bool {
+    matches!(self, Value::Missing)
   }
 
-  fn is_valid_pair(key: &str, val: &str) -> bool {
-    match key {
-      "byr" => {
-        // byr (Birth Year) - four digits; at least 1920 and at most 2002.
-        let year: u32 = val.parse().unwrap();
-        return year >= 1920 && year <= 2002;
-      }
-      "iyr" => {
-        // iyr (Issue Year) - four digits; at least 2010 and at most 2020.
-        let year: u32 = val.parse().unwrap();
-        return year >= 2010 && year <= 2020;
-      }
-      "eyr" => {
-        // eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
-        let year: u32 = val.parse().unwrap();
-        return year >= 2020 && year <= 2030;
+  /// Ok if the value is missing or passes `check`. A malformed value is invalid, for the reason
+  /// `malformed` gives.
+  fn check(
+    &self,
+    malformed: impl FnOnce(&str) -> String,
+    check: impl FnOnce(&T) -> Check,
+  ) -> Check {
+    match self {
+      Value::Missing => Ok(()),
+      Value::Typed(typed) => check(typed),
+      Value::Malformed { malformed: value } => Err(malformed(value)),
+    }
+  }
+}
+
+/// The fields of a passport as typed values. Whether the values are in range is for `check` to
+/// decide.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Passport {
+  pub byr: Value<u32>,
+  pub iyr: Value<u32>,
+  pub eyr: Value<u32>,
+  pub hgt: Value<Height>,
+  pub hcl: Value<HairColor>,
+  pub ecl: Value<EyeColor>,
+  /// Nine digits, leading zeros included.
+  pub pid: Value<String>,
+  pub cid: Value<String>,
+}
+
+impl Passport {
+  /// Types the first value of each known field, ignoring the rest even if the first one is
+  /// malformed.
+  pub fn from_fields(fields: &[Field]) -> Self {
+    let mut passport = Self::default();
+    let mut seen = HashSet::new();
+    for field in fields {
+      if !seen.insert(field.key.as_str()) {
+        continue;
       }
-      "hgt" => {
-        // hgt (Height) - a number followed by either cm or in:
-        //  - If cm, the number must be at least 150 and at most 193.
-        //  - If in, the number must be at least 59 and at most 76.
-        let caps = HEIGHT
-          .captures(val)
-          .expect(&format!("No height match for {}", val));
-        let num: i32 = caps["num"].parse().unwrap();
-        match &caps["unit"] {
-          "cm" => return num >= 150 && num <= 193,
-          "in" => return num >= 59 && num <= 76,
-          _ => return false,
+      let value = field.value.as_str();
+      match field.key.as_str() {
+        "byr" => passport.byr = Value::new(value, value.parse().ok()),
+        "iyr" => passport.iyr = Value::new(value, value.parse().ok()),
+        "eyr" => passport.eyr = Value::new(value, value.parse().ok()),
+        "hgt" => passport.hgt = Value::new(value, value.parse().ok()),
+        "hcl" => passport.hcl = Value::new(value, value.parse().ok()),
+        "ecl" => passport.ecl = Value::new(value, value.parse().ok()),
+        "pid" => {
+          let pid = Some(value.to_string()).filter(|pid| Self::is_pid(pid));
+          passport.pid = Value::new(value, pid);
         }
+        "cid" => passport.cid = Value::new(value, Some(value.to_string())),
+        _ => {}
       }
-      "hcl" => {
-        // hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
-        return HAIR_COLOR.is_match(val);
+    }
+    passport
+  }
+
+  fn is_pid(value: &str) -> bool {
+    value.len() == 9 && value.bytes().all(|b| b.is_ascii_digit())
+  }
+
+  /// Ok or why the value of the field `key` breaks the puzzle's rules. Missing fields are Ok here,
+  /// `check_required` reports them.
+  pub fn check(&self, key: &str) -> Check {
+    fn within(num: u32, unit: &str, range: RangeInclusive<u32>) -> Check {
+      if range.contains(&num) {
+        Ok(())
+      } else {
+        Err(format!("{}{} outside {}..={}", num, unit, range.start(), range.end()))
       }
-      "ecl" => {
-        // ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
-        return EYE_COLORS.contains(val);
+    }
+
+    // Worded like the reasons of `Schema::passport`.
+    let not_integer = |value: &str| format!("{:?} is not an integer", value);
+    let not = |expected: &'static str| move |value: &str| format!("{} is not {}", value, expected);
+    match key {
+      "byr" => self.byr.check(not_integer, |&year| within(year, "", 1920..=2002)),
+      "iyr" => self.iyr.check(not_integer, |&year| within(year, "", 2010..=2020)),
+      "eyr" => self.eyr.check(not_integer, |&year| within(year, "", 2020..=2030)),
+      "hgt" => self.hgt.check(not("an integer and a unit, cm or in"), |&height| match height {
+        Height::Cm(cm) => within(cm, "cm", 150..=193),
+        Height::In(inches) => within(inches, "in", 59..=76),
+      }),
+      "hcl" => self.hcl.check(not("# and six hex digits"), |_| Ok(())),
+      "ecl" => self.ecl.check(not("one of amb blu brn gry grn hzl oth"), |_| Ok(())),
+      "pid" => self.pid.check(not("nine digits"), |_| Ok(())),
+      "cid" => Ok(()),
+      _ => Err("unknown field".to_string()),
+    }
+  }
+
+  /// Whether every field but `cid` is present.
+  pub fn check_required(&self) -> Check {
+    let fields = [
+      ("byr", self.byr.is_missing()),
+      ("iyr", self.iyr.is_missing()),
+      ("eyr", self.eyr.is_missing()),
+      ("hgt", self.hgt.is_missing()),
+      ("hcl", self.hcl.is_missing()),
+      ("ecl", self.ecl.is_missing()),
+      ("pid", self.pid.is_missing()),
+    ];
+    let missing: Vec<&str> = fields
+      .iter()
+      .filter(|&&(_, missing)| missing)
+      .map(|&(key, _)| key)
+      .collect();
+    if missing.is_empty() {
+      Ok(())
+    } else {
+      Err(format!("missing {}", missing.join(", ")))
+    }
+  }
+}
+
+/// A passport as parsed: its raw fields, which custom schemas check, and the same fields typed.
+#[derive(Clone, Debug)]
+pub struct Record {
+  fields: Vec<Field>,
+  pub passport: Passport,
+}
+
+impl Record {
+  fn new(fields: Vec<Field>) -> Self {
+    let passport = Passport::from_fields(&fields);
+    Self { fields, passport }
+  }
+
+  /// The first and last line of the record. Groups are never empty, so neither are records.
+  fn lines(&self) -> (usize, usize) {
+    let first_line = self.fields.first().map_or(0, |field| field.line);
+    let last_line = self.fields.last().map_or(0, |field| field.line);
+    (first_line, last_line)
+  }
+}
+
+/// A passport with where it was found and whether each part counts it as valid.
+#[derive(Clone, Debug, Serialize)]
+pub struct Export {
+  pub first_line: usize,
+  pub last_line: usize,
+  pub part1: bool,
+  pub part2: bool,
+  /// The typed passport, under the puzzle's rules.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub passport: Option<Passport>,
+  /// The first value of each field, under a custom schema, whose keys may not be a passport's.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fields: Option<BTreeMap<String, String>>,
+}
+
+/// Checks typed passports against the puzzle's rules, or their raw fields against a `Schema`.
+pub struct DayFour {
+  schema: Option<Schema>,
+}
+
+impl DayFour {
+  /// Validates the raw fields against `schema` instead of the puzzle's rules.
+  pub fn with_schema(schema: Schema) -> Self {
+    Self {
+      schema: Some(schema),
+    }
+  }
+
+  fn is_valid_p1(&self, record: &Record) -> bool {
+    self.check_p1(record).is_ok()
+  }
+
+  fn check_p1(&self, record: &Record) -> Check {
+    let mut keys: HashSet<&str> = HashSet::new();
+
+    for field in &record.fields {
+      let new_val = keys.insert(&field.key);
+      if !new_val {
+        // Two entries for the same field!?
+        return Err(format!("duplicate {}", field.key));
       }
-      "pid" => {
-        // pid (Passport ID) - a nine-digit number, including leading zeroes.
-        return PASSPORT_ID.is_match(val);
+    }
+
+    self.check_keys(record, &keys)
+  }
+
+  fn is_valid_p2(&self, record: &Record) -> bool {
+    self.check_p2(record).is_ok()
+  }
+
+  /// Like `check_p1`, but also checks the values, malformed ones such as `byr:abc` included.
+  fn check_p2(&self, record: &Record) -> Check {
+    let mut keys: HashSet<&str> = HashSet::new();
+
+    for field in &record.fields {
+      let check = match &self.schema {
+        Some(schema) => schema.check_value(&field.key, &field.value),
+        None => record.passport.check(&field.key),
+      };
+      if let Err(reason) = check {
+        return Err(format!("{}: {}", field.key, reason));
       }
-      "cid" => {
-        // cid (Country ID) - ignored, missing or not.
-        return true;
+
+      let new_val = keys.insert(&field.key);
+      if !new_val {
+        // Two entries for the same field!?
+        return Err(format!("duplicate {}", field.key));
       }
-      _ => return false,
     }
+
+    self.check_keys(record, &keys)
+  }
+
+  /// Whether `record`, with the distinct `keys`, has every required field.
+  fn check_keys(&self, record: &Record, keys: &HashSet<&str>) -> Check {
+    match &self.schema {
+      Some(schema) => schema.check_keys(keys),
+      None => record.passport.check_required(),
+    }
+  }
+
+  /// Every record of `records` judged by both parts, with its typed passport or, under a custom
+  /// schema, its raw fields.
+  pub fn export(&self, records: &[Record]) -> Vec<Export> {
+    records
+      .iter()
+      .map(|record| {
+        let (first_line, last_line) = record.lines();
+        let fields = self.schema.as_ref().map(|_| {
+          let mut fields = BTreeMap::new();
+          for field in &record.fields {
+            fields.entry(field.key.clone()).or_insert_with(|| field.value.clone());
+          }
+          fields
+        });
+        Export {
+          first_line,
+          last_line,
+          part1: self.is_valid_p1(record),
+          part2: self.is_valid_p2(record),
+          passport: self.schema.is_none().then(|| record.passport.clone()),
+          fields,
+        }
+      })
+      .collect()
   }
 }
 
+crate::register!(2020, 4, DayFour);
+
 impl Problem for DayFour {
+  type Input = Vec<Record>;
+
   fn new() -> Self {
-    Self {}
+    Self { schema: None }
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(264.to_string())
-  }
+  fn parse(&self, input: &str) -> Result<Vec<Record>> {
+    // Passports are separated by blank lines.
+    let mut records = Vec::new();
+    for group in parse::groups(input) {
+      let mut fields = Vec::new();
+      for line in group {
+        let mut column = 1;
+        for detail in line.text.split(' ') {
+          let (key, value) = match detail.find(':') {
+            Some(colon) => (&detail[..colon], &detail[colon + 1..]),
+            None => return Err(line.error(column, format!("no value for {:?}", detail))),
+          };
+          fields.push(Field {
+            key: key.to_string(),
+            value: value.to_string(),
+            line: line.num,
+          });
+          column += detail.len() + 1;
+        }
+      }
+      records.push(Record::new(fields));
+    }
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let all_details = input.split("\n\n").filter(|line| !line.is_empty());
+    Ok(records)
+  }
 
-    let count: usize = all_details
-      .map(|details| self.is_valid_p1(details))
-      .filter(|&validity| validity == true)
+  fn part_one(&self, records: &Vec<Record>, _log: &Log) -> Result<String> {
+    let count: usize = records
+      .iter()
+      .filter(|record| self.is_valid_p1(record))
       .count();
-    Some(count.to_string())
+    Ok(count.to_string())
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some(224.to_string())
+  fn part_two(&self, records: &Vec<Record>, _log: &Log) -> Result<String> {
+    let count: usize = records
+      .iter()
+      .filter(|record| self.is_valid_p2(record))
+      .count();
+    Ok(count.to_string())
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let all_details = input.split("\n\n").filter(|line| !line.is_empty());
-
-    let count: usize = all_details
-      .map(|details| self.is_valid_p2(details))
-      .filter(|&validity| validity == true)
-      .count();
-    Some(count.to_string())
+  fn validate(&self, records: &Vec<Record>, part: u8) -> Result<Vec<Verdict>> {
+    let mut verdicts = Vec::new();
+    for record in records {
+      let check = if part == 1 {
+        self.check_p1(record)
+      } else {
+        self.check_p2(record)
+      };
+      let (first_line, last_line) = record.lines();
+      verdicts.push(Verdict::new(first_line, last_line, check));
+    }
+    Ok(verdicts)
   }
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::day04::DayFour;
+  use proptest::prelude::*;
+
+  use crate::day04::{DayFour, EyeColor, Field, HairColor, Height, Passport, Record, Value};
+  use crate::log::Log;
+  use crate::problem::Problem;
+  use crate::schema::Schema;
+
+  fn is_valid_pair(key: &str, val: &str) -> bool {
+    Schema::passport().check_value(key, val).is_ok()
+  }
 
   #[test]
   fn byr() {
-    assert_eq!(DayFour::is_valid_pair("byr", "2002"), true);
-    assert_eq!(DayFour::is_valid_pair("byr", "2003"), false);
+    assert!(is_valid_pair("byr", "2002"));
+    assert!(!is_valid_pair("byr", "2003"));
   }
 
   #[test]
   fn hgt() {
-    assert_eq!(DayFour::is_valid_pair("hgt", "60in"), true);
-    assert_eq!(DayFour::is_valid_pair("hgt", "190cm"), true);
-    assert_eq!(DayFour::is_valid_pair("hgt", "190in"), false);
-    assert_eq!(DayFour::is_valid_pair("hgt", "190"), false);
+    assert!(is_valid_pair("hgt", "60in"));
+    assert!(is_valid_pair("hgt", "190cm"));
+    assert!(!is_valid_pair("hgt", "190in"));
+    assert!(!is_valid_pair("hgt", "190"));
   }
 
   #[test]
   fn hcl() {
-    assert_eq!(DayFour::is_valid_pair("hcl", "#123abc"), true);
-    assert_eq!(DayFour::is_valid_pair("hcl", "#123abz"), false);
-    assert_eq!(DayFour::is_valid_pair("hcl", "123abc"), false);
+    assert!(is_valid_pair("hcl", "#123abc"));
+    assert!(!is_valid_pair("hcl", "#123abz"));
+    assert!(!is_valid_pair("hcl", "123abc"));
   }
 
   #[test]
   fn ecl() {
-    assert_eq!(DayFour::is_valid_pair("ecl", "brn"), true);
-    assert_eq!(DayFour::is_valid_pair("ecl", "wat"), false);
+    assert!(is_valid_pair("ecl", "brn"));
+    assert!(!is_valid_pair("ecl", "wat"));
+  }
+
+  #[test]
+  fn malformed() {
+    assert!(!is_valid_pair("byr", "abc"));
+    assert!(!is_valid_pair("hgt", "tall"));
+    assert!(!is_valid_pair("iyr", "99999999999999999999"));
+
+    let day = DayFour::new();
+    let passports = day
+      .parse("byr:abc iyr:2015 eyr:2025 hgt:tall hcl:#123abc ecl:brn pid:000000001\n")
+      .unwrap();
+    assert_eq!(day.part_two(&passports, &Log::off()), Ok("0".to_string()));
+    let verdicts = day.validate(&passports, 2).unwrap();
+    assert_eq!(verdicts[0].reason.as_deref(), Some("byr: \"abc\" is not an integer"));
+  }
+
+  fn field() -> impl Strategy<Value = Field> {
+    let keys = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid", "xyz"];
+    let value = "[0-9]{0,25}(cm|in|ft)?|#?[0-9a-fz]{0,8}|\\PC{0,12}|(19|20)[0-9]{2}|1[5-9][0-9]cm|\
+                 [5-7][0-9]in|[a-z]{3}";
+    (prop::sample::select(keys), value).prop_map(|(key, value)| Field {
+      key: key.to_string(),
+      value,
+      line: 1,
+    })
+  }
+
+  proptest! {
+    #[test]
+    fn never_panics(fields in prop::collection::vec(field(), 0..10)) {
+      let day = DayFour::new();
+      let record = Record::new(fields);
+      let valid = day.is_valid_p2(&record);
+      prop_assert_eq!(valid, day.check_p2(&record).is_ok());
+      prop_assert!(!valid || day.is_valid_p1(&record));
+    }
+
+    #[test]
+    fn typed_rules_match_schema(fields in prop::collection::vec(field(), 0..10)) {
+      let record = Record::new(fields);
+      let (typed, schema) = (DayFour::new(), DayFour::with_schema(Schema::passport()));
+      prop_assert_eq!(typed.check_p1(&record), schema.check_p1(&record));
+      prop_assert_eq!(typed.is_valid_p2(&record), schema.is_valid_p2(&record));
+    }
+  }
+
+  #[test]
+  fn reasons() {
+    assert_eq!(
+      Schema::passport().check_value("hgt", "190in"),
+      Err("190in outside 59..=76".to_string())
+    );
+    assert_eq!(
+      Schema::passport().check_value("ecl", "wat"),
+      Err("wat is not one of amb blu brn gry grn hzl oth".to_string())
+    );
+
+    let day = DayFour::new();
+    let passports = day
+      .parse(
+        "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\n\
+         byr:1937 iyr:2017 hgt:183cm\n\nhgt:59cm\n",
+      )
+      .unwrap();
+    let verdicts = day.validate(&passports, 2).unwrap();
+    assert_eq!((verdicts[0].first_line, verdicts[0].last_line), (1, 2));
+    assert!(verdicts[0].valid);
+    assert_eq!(verdicts[1].reason.as_deref(), Some("hgt: 59cm outside 150..=193"));
+    let verdicts = day.validate(&passports, 1).unwrap();
+    assert_eq!(
+      verdicts[1].reason.as_deref(),
+      Some("missing byr, iyr, eyr, hcl, ecl, pid")
+    );
+  }
+
+  #[test]
+  fn custom_schema() {
+    let schema = Schema::parse("[[field]]\nkey = \"id\"\nrange = [1, 9]\n").unwrap();
+    let day = DayFour::with_schema(schema);
+    let passports = day.parse("id:3\n\nid:12\n\nname:x\n").unwrap();
+    assert_eq!(day.part_one(&passports, &Log::off()), Ok("2".to_string()));
+    assert_eq!(day.part_two(&passports, &Log::off()), Ok("1".to_string()));
+
+    let json = serde_json::to_value(&day.export(&passports)[1]).unwrap();
+    assert_eq!(json["fields"]["id"], "12");
+    assert!(json.get("passport").is_none());
+  }
+
+  #[test]
+  fn typed() {
+    let day = DayFour::new();
+    let passports = day
+      .parse(
+        "byr:1980 hgt:70in hcl:#0a10ff ecl:grn pid:000000012\n\n\
+         byr:abc hgt:tall hcl:red pid:012\n\nbyr:abc byr:1980 pid:0123456789 pid:000000012\n",
+      )
+      .unwrap();
+    assert_eq!(
+      passports[0].passport,
+      Passport {
+        byr: Value::Typed(1980),
+        hgt: Value::Typed(Height::In(70)),
+        hcl: Value::Typed(HairColor { r: 10, g: 16, b: 255 }),
+        ecl: Value::Typed(EyeColor::Grn),
+        pid: Value::Typed("000000012".to_string()),
+        ..Passport::default()
+      }
+    );
+    fn malformed<T>(value: &str) -> Value<T> {
+      Value::Malformed {
+        malformed: value.to_string(),
+      }
+    }
+    assert_eq!(
+      passports[1].passport,
+      Passport {
+        byr: malformed("abc"),
+        hgt: malformed("tall"),
+        hcl: malformed("red"),
+        pid: malformed("012"),
+        ..Passport::default()
+      }
+    );
+    assert_eq!(passports[2].passport.byr, malformed("abc"));
+    assert_eq!(passports[2].passport.pid, malformed("0123456789"));
+
+    let export = day.export(&passports);
+    let json = serde_json::to_value(&export[0]).unwrap();
+    assert_eq!(json["part1"], false);
+    assert_eq!(json["passport"]["hgt"]["in"], 70);
+    assert_eq!(json["passport"]["ecl"], "grn");
+    assert_eq!(json["passport"]["hcl"]["b"], 255);
+    assert!(json["passport"]["iyr"].is_null());
+    assert!(json.get("fields").is_none());
+    let json = serde_json::to_value(&export[1]).unwrap();
+    assert_eq!(json["passport"]["hgt"]["malformed"], "tall");
   }
 
   #[test]
   fn pid() {
-    assert_eq!(DayFour::is_valid_pair("pid", "000000001"), true);
-    assert_eq!(DayFour::is_valid_pair("pid", "0123456789"), false);
+    assert!(is_valid_pair("pid", "000000001"));
+    assert!(!is_valid_pair("pid", "0123456789"));
   }
 }
@@ -1,3 +1,6 @@
+use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::parse;
 use crate::problem::Problem;
 
 use std::collections::HashSet;
@@ -6,58 +9,51 @@ use std::collections::HashSet;
 pub struct DayFive {}
 
 impl DayFive {
-  fn seat_id(seat: &str) -> u16 {
-    let binary: String = seat
-      .chars()
-      .map(|letter| match letter {
-        'F' => '0',
-        'B' => '1',
-        'L' => '0',
-        'R' => '1',
-        _ => panic!("Unexpected char found!"),
-      })
-      .collect();
-
-    u16::from_str_radix(&binary, 2).unwrap()
+  /// Returns the seat ID, or the 0-based index of the first character that is not F/B/L/R.
+  fn seat_id(seat: &str) -> std::result::Result<u16, usize> {
+    let mut id: u16 = 0;
+    for (idx, letter) in seat.chars().enumerate() {
+      let bit = match letter {
+        'F' => 0,
+        'B' => 1,
+        'L' => 0,
+        'R' => 1,
+        _ => return Err(idx),
+      };
+      id = id.checked_mul(2).ok_or(idx)? | bit;
+    }
+    Ok(id)
   }
 }
 
+crate::register!(2020, 5, DayFive);
+
 impl Problem for DayFive {
+  type Input = Vec<u16>;
+
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(935.to_string())
-  }
-
-  fn part_one(&self, input: &str) -> Option<String> {
-    let max = input
-      .split('\n')
-      .filter(|line| !line.is_empty())
-      .map(|seat| DayFive::seat_id(seat))
-      .max()
-      .unwrap();
-    Some(max.to_string())
+  fn parse(&self, input: &str) -> Result<Vec<u16>> {
+    parse::map_lines(input, |line| {
+      Self::seat_id(line.text)
+        .map_err(|column| line.error(column + 1, format!("invalid seat {:?}", line.text)))
+    })
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some(743.to_string())
+  fn part_one(&self, seat_ids: &Vec<u16>, _log: &Log) -> Result<String> {
+    let max = seat_ids.iter().max().ok_or(Error::NoSolution)?;
+    Ok(max.to_string())
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let seat_ids: HashSet<u16> = input
-      .split('\n')
-      .filter(|line| !line.is_empty())
-      .map(|seat| DayFive::seat_id(seat))
-      .collect();
+  fn part_two(&self, seat_ids: &Vec<u16>, _log: &Log) -> Result<String> {
+    let seat_ids: HashSet<u16> = seat_ids.iter().copied().collect();
 
-    let my_seat_id = (0..1023)
-      .filter(|id| {
-        !seat_ids.contains(id) && seat_ids.contains(&(id + 1)) && seat_ids.contains(&(id - 1))
-      })
-      .next();
-    my_seat_id.map(|id| id.to_string())
+    let my_seat_id = (1..1023).find(|id| {
+      !seat_ids.contains(id) && seat_ids.contains(&(id + 1)) && seat_ids.contains(&(id - 1))
+    });
+    my_seat_id.map(|id| id.to_string()).ok_or(Error::NoSolution)
   }
 }
 
@@ -67,8 +63,9 @@ mod tests {
 
   #[test]
   fn seat_id() {
-    assert_eq!(DayFive::seat_id("BFFFBBFRRR"), 567);
-    assert_eq!(DayFive::seat_id("FFFBBBFRRR"), 119);
-    assert_eq!(DayFive::seat_id("BBFFBBFRLL"), 820);
+    assert_eq!(DayFive::seat_id("BFFFBBFRRR"), Ok(567));
+    assert_eq!(DayFive::seat_id("FFFBBBFRRR"), Ok(119));
+    assert_eq!(DayFive::seat_id("BBFFBBFRLL"), Ok(820));
+    assert_eq!(DayFive::seat_id("BBFXBBFRLL"), Err(3));
   }
 }
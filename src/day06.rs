@@ -1,3 +1,6 @@
+use crate::error::Result;
+use crate::log::Log;
+use crate::parse;
 use crate::problem::Problem;
 
 use std::collections::HashSet;
@@ -6,54 +9,51 @@ use std::collections::HashSet;
 pub struct DaySix {}
 
 impl DaySix {
-  fn get_uniq_count(lines: &str) -> usize {
-    let set: HashSet<char> = lines.chars().filter(|c| *c != '\n').collect();
+  fn get_uniq_count(lines: &[String]) -> usize {
+    let set: HashSet<char> = lines.iter().flat_map(|line| line.chars()).collect();
     set.len()
   }
 
-  fn get_common_count(lines: &str) -> usize {
+  fn get_common_count(lines: &[String]) -> usize {
     fn intersection(a: HashSet<char>, b: HashSet<char>) -> HashSet<char> {
       a.into_iter().filter(|e| b.contains(e)).collect()
     }
 
-    let mut map = lines
-      .split("\n")
-      .filter(|line| !line.is_empty())
-      .map(|line| line.chars().collect());
-    let set: HashSet<char> = map.next().unwrap();
-    let set = map.fold(set, |a, b| intersection(a, b));
+    let mut map = lines.iter().map(|line| line.chars().collect());
+    let set: HashSet<char> = match map.next() {
+      Some(set) => set,
+      None => return 0,
+    };
+    let set = map.fold(set, intersection);
 
     set.len()
   }
 }
 
+crate::register!(2020, 6, DaySix);
+
 impl Problem for DaySix {
+  type Input = Vec<Vec<String>>;
+
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(6878.to_string())
-  }
-
-  fn part_one(&self, input: &str) -> Option<String> {
-    let total: usize = input
-      .split("\n\n")
-      .map(|group| Self::get_uniq_count(group))
-      .sum();
-    Some(total.to_string())
+  fn parse(&self, input: &str) -> Result<Vec<Vec<String>>> {
+    let groups = parse::groups(input)
+      .into_iter()
+      .map(|group| group.iter().map(|line| line.text.to_string()).collect());
+    Ok(groups.collect())
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some(3464.to_string())
+  fn part_one(&self, groups: &Vec<Vec<String>>, _log: &Log) -> Result<String> {
+    let total: usize = groups.iter().map(|group| Self::get_uniq_count(group)).sum();
+    Ok(total.to_string())
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let total: usize = input
-      .split("\n\n")
-      .map(|group| Self::get_common_count(group))
-      .sum();
-    Some(total.to_string())
+  fn part_two(&self, groups: &Vec<Vec<String>>, _log: &Log) -> Result<String> {
+    let total: usize = groups.iter().map(|group| Self::get_common_count(group)).sum();
+    Ok(total.to_string())
   }
 }
 
@@ -61,12 +61,16 @@ impl Problem for DaySix {
 mod tests {
   use crate::day06::DaySix;
 
+  fn group(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+  }
+
   #[test]
   fn get_uniq_count() {
-    assert_eq!(DaySix::get_uniq_count("abc"), 3);
-    assert_eq!(DaySix::get_uniq_count("a\nb\nc\n"), 3);
-    assert_eq!(DaySix::get_uniq_count("ab\nac"), 3);
-    assert_eq!(DaySix::get_uniq_count("a\na\na\na\n"), 1);
-    assert_eq!(DaySix::get_uniq_count("b\n"), 1);
+    assert_eq!(DaySix::get_uniq_count(&group(&["abc"])), 3);
+    assert_eq!(DaySix::get_uniq_count(&group(&["a", "b", "c"])), 3);
+    assert_eq!(DaySix::get_uniq_count(&group(&["ab", "ac"])), 3);
+    assert_eq!(DaySix::get_uniq_count(&group(&["a", "a", "a", "a"])), 1);
+    assert_eq!(DaySix::get_uniq_count(&group(&["b"])), 1);
   }
 }
@@ -1,3 +1,6 @@
+use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::parse::{self, Line};
 use crate::problem::Problem;
 
 use std::collections::HashMap;
@@ -5,7 +8,7 @@ use std::collections::HashMap;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-const TARGET_BAG: &'static str = "shiny gold";
+const TARGET_BAG: &str = "shiny gold";
 
 lazy_static! {
   static ref FULL: Regex = Regex::new(r"^(\w+ \w+) bags? contain (.+)\.$").unwrap();
@@ -13,9 +16,11 @@ lazy_static! {
   static ref TERMINAL: Regex = Regex::new(r"^no other bags$").unwrap();
 }
 
+/// bag -> (child_bag, count of child_bag)
+pub type Rules = HashMap<String, HashMap<String, usize>>;
+
 struct Traverser<'a> {
-  // bag -> (child_bag, count of child_bag)
-  graph: HashMap<&'a str, HashMap<&'a str, usize>>,
+  graph: &'a Rules,
   // bag -> whether bag contains TARGET_BAG in descendants
   contains_target: HashMap<&'a str, bool>,
   // bag -> how many total descendent bags contained in bag
@@ -23,38 +28,17 @@ struct Traverser<'a> {
 }
 
 impl<'a> Traverser<'a> {
-  fn new(lines: Vec<&'a str>) -> Self {
+  fn new(graph: &'a Rules) -> Self {
     Self {
-      graph: lines.iter().map(|line| Self::parse_line(line)).collect(),
+      graph,
       contains_target: HashMap::new(),
       contained_bags: HashMap::new(),
     }
   }
 
-  fn parse_line(line: &str) -> (&str, HashMap<&str, usize>) {
-    let caps = FULL.captures(line).unwrap();
-    let key = caps.get(1).unwrap().as_str();
-    let bags = caps.get(2).unwrap().as_str();
-
-    let mut map = HashMap::new();
-    if TERMINAL.is_match(bags) {
-      return (key, map);
-    }
-
-    for pair in bags.split(", ") {
-      let pair_caps = PAIR
-        .captures(pair)
-        .expect(&format!("Failed match on {}", pair));
-      let num = pair_caps.get(1).unwrap().as_str().parse().unwrap();
-      let bag = pair_caps.get(2).unwrap().as_str();
-      map.insert(bag, num);
-    }
-    (key, map)
-  }
-
   fn count_carriers(&mut self) -> usize {
     let mut count = 0;
-    let all_bags: Vec<&str> = self.graph.keys().copied().collect();
+    let all_bags: Vec<&str> = self.graph.keys().map(String::as_str).collect();
     for bag in all_bags {
       if self.is_carrier(TARGET_BAG, bag) {
         count += 1;
@@ -71,7 +55,10 @@ impl<'a> Traverser<'a> {
       }
       None => {
         let mut result = false;
-        let children: Vec<&str> = self.graph[bag].keys().copied().collect();
+        let children: Vec<&str> = match self.graph.get(bag) {
+          Some(children) => children.keys().map(String::as_str).collect(),
+          None => Vec::new(),
+        };
         for child in children {
           result |= (child == target_bag) || self.is_carrier(target_bag, child);
         }
@@ -86,7 +73,10 @@ impl<'a> Traverser<'a> {
       Some(count) => *count,
       None => {
         let mut total = 1;
-        let children: Vec<(&str, usize)> = self.graph[bag].iter().map(|(k, v)| (*k, *v)).collect();
+        let children: Vec<(&str, usize)> = match self.graph.get(bag) {
+          Some(children) => children.iter().map(|(k, v)| (k.as_str(), *v)).collect(),
+          None => Vec::new(),
+        };
         for (child, num) in children {
           total += num * self.count_contained_bags(child);
         }
@@ -100,56 +90,61 @@ impl<'a> Traverser<'a> {
 #[derive(Default)]
 pub struct DaySeven {}
 
-impl DaySeven {}
+impl DaySeven {
+  fn parse_line(line: Line) -> Result<(String, HashMap<String, usize>)> {
+    let caps = parse::captures(&FULL, line, "rule")?;
+    let key = caps[1].to_string();
+    let bags = caps.get(2).unwrap();
+
+    let mut map = HashMap::new();
+    if TERMINAL.is_match(bags.as_str()) {
+      return Ok((key, map));
+    }
+
+    let mut column = bags.start() + 1;
+    for pair in bags.as_str().split(", ") {
+      let invalid = || line.error(column, format!("invalid contents {:?}", pair));
+      let pair_caps = PAIR.captures(pair).ok_or_else(invalid)?;
+      let num = pair_caps[1].parse().map_err(|_| invalid())?;
+      let bag = pair_caps[2].to_string();
+      map.insert(bag, num);
+      column += pair.len() + 2;
+    }
+    Ok((key, map))
+  }
+}
+
+crate::register!(2020, 7, DaySeven);
 
 impl Problem for DaySeven {
+  type Input = Rules;
+
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(126.to_string())
+  fn parse(&self, input: &str) -> Result<Rules> {
+    let rules = parse::map_lines(input, Self::parse_line)?;
+    Ok(rules.into_iter().collect())
   }
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let lines: Vec<&str> = input.split("\n").filter(|line| !line.is_empty()).collect();
-    let mut traverser = Traverser::new(lines);
+  fn part_one(&self, rules: &Rules, _log: &Log) -> Result<String> {
+    let mut traverser = Traverser::new(rules);
 
     let num_carriers = traverser.count_carriers();
 
-    Some(num_carriers.to_string())
+    Ok(num_carriers.to_string())
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some(220149.to_string())
-  }
-
-  fn part_two(&self, input: &str) -> Option<String> {
-    let lines: Vec<&str> = input.split("\n").filter(|line| !line.is_empty()).collect();
-    let mut traverser = Traverser::new(lines);
+  fn part_two(&self, rules: &Rules, _log: &Log) -> Result<String> {
+    if !rules.contains_key(TARGET_BAG) {
+      return Err(Error::NoSolution);
+    }
 
+    let mut traverser = Traverser::new(rules);
     // -1 because we don't want to count the target bag itself.
     let total_contained_bags = traverser.count_contained_bags(TARGET_BAG) - 1;
 
-    Some(total_contained_bags.to_string())
-  }
-}
-
-#[cfg(test)]
-mod tests {
-  use crate::day07::DaySeven;
-  use crate::problem::Problem;
-
-  #[test]
-  fn part_two_given() {
-    let problem = DaySeven::new();
-    let input = "shiny gold bags contain 2 dark red bags.\n\
-                 dark red bags contain 2 dark orange bags.\n\
-                 dark orange bags contain 2 dark yellow bags.\n\
-                 dark yellow bags contain 2 dark green bags.\n\
-                 dark green bags contain 2 dark blue bags.\n\
-                 dark blue bags contain 2 dark violet bags.\n\
-                 dark violet bags contain no other bags.\n";
-    assert_eq!(problem.part_two(input).unwrap(), "126".to_string());
+    Ok(total_contained_bags.to_string())
   }
 }
@@ -1,94 +1,98 @@
+use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::parse::{self, Line};
 use crate::problem::Problem;
 
 use std::collections::HashSet;
 
 #[derive(Clone, Copy, Debug)]
 enum Operation {
-  ACC,
-  JMP,
-  NOP,
+  Acc,
+  Jmp,
+  Nop,
 }
 
 #[derive(Clone, Copy, Debug)]
-struct Instruction(Operation, i32);
+pub struct Instruction(Operation, i32);
 
 #[derive(Default)]
 pub struct DayEight {}
 
 impl DayEight {
-  fn parse_instruction(line: &str) -> Instruction {
-    let mut parts = line.split(' ');
+  fn parse_instruction(line: Line) -> Result<Instruction> {
+    let mut parts = line.text.split(' ');
     let operation = match parts.next().unwrap() {
-      "acc" => Operation::ACC,
-      "jmp" => Operation::JMP,
-      "nop" => Operation::NOP,
-      _ => panic!("Cannot parse {}", line),
+      "acc" => Operation::Acc,
+      "jmp" => Operation::Jmp,
+      "nop" => Operation::Nop,
+      op => return Err(line.error(1, format!("unknown operation {:?}", op))),
     };
     let argument = parts
       .next()
-      .unwrap()
-      .parse()
-      .expect(&format!("No argument in {}", line));
-    Instruction(operation, argument)
+      .and_then(|arg| arg.parse().ok())
+      .ok_or_else(|| line.error(5, format!("no argument in {:?}", line.text)))?;
+    Ok(Instruction(operation, argument))
   }
 
   fn execute(instruction: &Instruction, ipr: &mut usize, acc: &mut i32) {
     match instruction.0 {
-      Operation::ACC => {
+      Operation::Acc => {
         *acc += instruction.1;
         *ipr += 1;
       }
-      Operation::JMP => {
+      Operation::Jmp => {
         *ipr = ipr.wrapping_add(instruction.1 as usize);
       }
-      Operation::NOP => {
+      Operation::Nop => {
         *ipr += 1;
       }
     }
   }
 
-  fn acc_before_duplicate(program: &Vec<Instruction>) -> i32 {
+  /// None if the program never repeats an instruction.
+  fn acc_before_duplicate(program: &[Instruction]) -> Option<i32> {
     let mut iprs_seen: HashSet<usize> = HashSet::new();
     let mut ipr: usize = 0;
     let mut acc: i32 = 0;
 
     while !iprs_seen.contains(&ipr) {
-      let instruction = &program[ipr];
+      let instruction = program.get(ipr)?;
       iprs_seen.insert(ipr);
       Self::execute(instruction, &mut ipr, &mut acc);
     }
 
-    acc
+    Some(acc)
   }
 
-  fn acc_after_flip(program: &Vec<Instruction>) -> i32 {
+  /// None if no single flip makes the program terminate.
+  fn acc_after_flip(program: &[Instruction]) -> Option<i32> {
     let mut program_copy = program.to_vec();
     let mut iprs_seen: HashSet<usize> = HashSet::new();
     let mut ipr: usize = 0;
     let mut acc: i32 = 0;
 
     while !iprs_seen.contains(&ipr) {
-      let instruction: Instruction = program_copy[ipr];
+      let instruction: Instruction = *program_copy.get(ipr)?;
 
       // Try flipping and running if possible.
       match instruction.0 {
-        Operation::ACC => {
+        Operation::Acc => {
           // No flip, continue as normal.
         }
-        Operation::JMP => {
-          program_copy[ipr] = Instruction(Operation::NOP, instruction.1);
+        Operation::Jmp => {
+          program_copy[ipr] = Instruction(Operation::Nop, instruction.1);
           if let Some(final_acc) = Self::does_terminate(&program_copy, iprs_seen.clone(), ipr, acc)
           {
-            return final_acc;
+            return Some(final_acc);
           }
           // Didn't work, let's flip back.
           program_copy[ipr] = instruction;
         }
-        Operation::NOP => {
-          program_copy[ipr] = Instruction(Operation::JMP, instruction.1);
+        Operation::Nop => {
+          program_copy[ipr] = Instruction(Operation::Jmp, instruction.1);
           if let Some(final_acc) = Self::does_terminate(&program_copy, iprs_seen.clone(), ipr, acc)
           {
-            return final_acc;
+            return Some(final_acc);
           }
           // Didn't work, let's flip back.
           program_copy[ipr] = instruction;
@@ -100,13 +104,14 @@ impl DayEight {
       Self::execute(&instruction, &mut ipr, &mut acc);
     }
 
-    panic!("Could not find a flip to terminate the program!");
+    // Could not find a flip to terminate the program!
+    None
   }
 
   /// Some(acc) if program terminates, None if it doesn't
   /// Call this after flipping NOP <-> JMP in program
   fn does_terminate(
-    program: &Vec<Instruction>,
+    program: &[Instruction],
     mut iprs_seen: HashSet<usize>,
     mut ipr: usize,
     mut acc: i32,
@@ -117,7 +122,7 @@ impl DayEight {
         return Some(acc);
       }
 
-      let instruction = &program[ipr];
+      let instruction = program.get(ipr)?;
       iprs_seen.insert(ipr);
       Self::execute(instruction, &mut ipr, &mut acc);
     }
@@ -127,72 +132,46 @@ impl DayEight {
   }
 }
 
+crate::register!(2020, 8, DayEight);
+
 impl Problem for DayEight {
+  type Input = Vec<Instruction>;
+
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(1548.to_string())
+  fn parse(&self, input: &str) -> Result<Vec<Instruction>> {
+    parse::map_lines(input, Self::parse_instruction)
   }
 
-  fn part_one(&self, input: &str) -> Option<String> {
-    let instructions: Vec<Instruction> = input
-      .lines()
-      .map(|line| DayEight::parse_instruction(line))
-      .collect();
-
-    let acc = DayEight::acc_before_duplicate(&instructions);
-    Some(acc.to_string())
+  fn part_one(&self, instructions: &Vec<Instruction>, _log: &Log) -> Result<String> {
+    let acc = DayEight::acc_before_duplicate(instructions).ok_or(Error::NoSolution)?;
+    Ok(acc.to_string())
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some(1375.to_string())
-  }
-
-  fn part_two(&self, input: &str) -> Option<String> {
-    let instructions: Vec<Instruction> = input
-      .lines()
-      .map(|line| DayEight::parse_instruction(line))
-      .collect();
-
-    let acc = DayEight::acc_after_flip(&instructions);
-    Some(acc.to_string())
+  fn part_two(&self, instructions: &Vec<Instruction>, _log: &Log) -> Result<String> {
+    let acc = DayEight::acc_after_flip(instructions).ok_or(Error::NoSolution)?;
+    Ok(acc.to_string())
   }
 }
 
 #[cfg(test)]
 mod tests {
   use crate::day08::DayEight;
+  use crate::error::Error;
   use crate::problem::Problem;
 
   #[test]
-  fn part_one_given() {
-    let problem = DayEight::new();
-    let input = "nop +0\n\
-                 acc +1\n\
-                 jmp +4\n\
-                 acc +3\n\
-                 jmp -3\n\
-                 acc -99\n\
-                 acc +1\n\
-                 jmp -4\n\
-                 acc +6\n";
-    assert_eq!(problem.part_one(input).unwrap(), "5".to_string());
-  }
-
-  #[test]
-  fn part_two_given() {
+  fn malformed() {
     let problem = DayEight::new();
-    let input = "nop +0\n\
-                 acc +1\n\
-                 jmp +4\n\
-                 acc +3\n\
-                 jmp -3\n\
-                 acc -99\n\
-                 acc +1\n\
-                 jmp -4\n\
-                 acc +6\n";
-    assert_eq!(problem.part_two(input).unwrap(), "8".to_string());
+    assert_eq!(
+      problem.parse("nop +0\nhop +1\n").err(),
+      Some(Error::parse(2, 1, "unknown operation \"hop\""))
+    );
+    assert_eq!(
+      problem.parse("nop +0\nacc\n").err(),
+      Some(Error::parse(2, 5, "no argument in \"acc\""))
+    );
   }
 }
@@ -1,3 +1,6 @@
+use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::parse;
 use crate::problem::Problem;
 
 use std::collections::HashSet;
@@ -6,7 +9,7 @@ use std::collections::HashSet;
 pub struct DayNine {}
 
 impl DayNine {
-  fn find_invalid_naive(nums: &Vec<i64>, preamble: usize) -> Option<&i64> {
+  fn find_invalid_naive(nums: &[i64], preamble: usize) -> Option<&i64> {
     // Checks if prev_nums has any two nums that add up to target.
     let has_complements = |prev_nums: &[i64], target: &i64| -> bool {
       let mut complements: HashSet<i64> = HashSet::with_capacity(preamble);
@@ -33,57 +36,57 @@ impl DayNine {
 
   /// Returns the start and end indices (inclusive) of a contiguous set of elements that sum up to
   /// target.
-  fn find_contiguous(nums: &Vec<i64>, target: &i64) -> (usize, usize) {
+  fn find_contiguous(nums: &[i64], target: &i64) -> Option<(usize, usize)> {
+    if nums.len() < 2 {
+      return None;
+    }
     let (mut start, mut end) = (0, 1);
     let mut curr_sum = nums[start] + nums[end];
 
     while end < nums.len() {
-      if start >= end {
-        panic!("Invalid state: start={} end={}", start, end);
-      }
-
       if curr_sum == *target {
-        return (start, end);
-      } else if curr_sum < *target {
+        return Some((start, end));
+      } else if curr_sum < *target || start + 1 >= end {
         end += 1;
-        curr_sum += nums[end];
+        curr_sum += nums.get(end)?;
       } else {
         curr_sum -= nums[start];
         start += 1;
       }
     }
 
-    panic!("No contiguous set found!");
+    // No contiguous set found!
+    None
   }
 }
 
+crate::register!(2020, 9, DayNine);
+
 impl Problem for DayNine {
+  type Input = Vec<i64>;
+
   fn new() -> Self {
     Self {}
   }
 
-  fn soln_one(&self) -> Option<String> {
-    Some(1504371145.to_string())
-  }
-
-  fn part_one(&self, input: &str) -> Option<String> {
-    let nums: Vec<i64> = input.lines().map(|line| line.parse().unwrap()).collect();
-    Self::find_invalid_naive(&nums, 25).map(|num| num.to_string())
+  fn parse(&self, input: &str) -> Result<Vec<i64>> {
+    parse::numbers(input)
   }
 
-  fn soln_two(&self) -> Option<String> {
-    Some(183278487.to_string())
+  fn part_one(&self, nums: &Vec<i64>, _log: &Log) -> Result<String> {
+    Self::find_invalid_naive(nums, 25)
+      .map(|num| num.to_string())
+      .ok_or(Error::NoSolution)
   }
 
-  fn part_two(&self, input: &str) -> Option<String> {
-    let nums: Vec<i64> = input.lines().map(|line| line.parse().unwrap()).collect();
-    let target: &i64 = Self::find_invalid_naive(&nums, 25).unwrap();
+  fn part_two(&self, nums: &Vec<i64>, _log: &Log) -> Result<String> {
+    let target: &i64 = Self::find_invalid_naive(nums, 25).ok_or(Error::NoSolution)?;
 
-    let (start_idx, end_idx) = Self::find_contiguous(&nums, target);
+    let (start_idx, end_idx) = Self::find_contiguous(nums, target).ok_or(Error::NoSolution)?;
     let answer = nums[start_idx..end_idx].iter().max().unwrap()
       + nums[start_idx..end_idx].iter().min().unwrap();
 
-    Some(answer.to_string())
+    Ok(answer.to_string())
   }
 }
 
@@ -91,6 +94,8 @@ impl Problem for DayNine {
 mod tests {
   use crate::day09::DayNine;
 
+  // The puzzle example has a preamble of 5 rather than 25, so it is checked here instead of in
+  // examples/day09/.
   #[test]
   fn find_invalid_naive_given() {
     let nums: Vec<i64> = vec![
@@ -104,6 +109,7 @@ mod tests {
     let nums: Vec<i64> = vec![
       35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
     ];
-    assert_eq!(DayNine::find_contiguous(&nums, &127), (2, 5));
+    assert_eq!(DayNine::find_contiguous(&nums, &127), Some((2, 5)));
+    assert_eq!(DayNine::find_contiguous(&nums, &1), None);
   }
 }
@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// Everything that can go wrong while solving a part of a problem.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+  /// The input could not be read.
+  Input(String),
+  /// The input is malformed. `line` and `column` are 1-based.
+  Parse {
+    line: usize,
+    column: usize,
+    message: String,
+  },
+  /// The input is well-formed, but has no answer.
+  NoSolution,
+  /// The part has not been solved yet.
+  Unimplemented,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+  pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+    Error::Parse {
+      line,
+      column,
+      message: message.into(),
+    }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::Parse {
+        line,
+        column,
+        message,
+      } => write!(f, "parse error at {}:{}: {}", line, column, message),
+      Error::Input(message) => write!(f, "could not read input: {}", message),
+      Error::NoSolution => write!(f, "no solution found"),
+      Error::Unimplemented => write!(f, "unimplemented"),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,263 @@
+use std::fmt;
+
+use crate::error::Result;
+use crate::parse;
+
+/// A position in a grid as (x, y), with (0, 0) at the top left.
+pub type Pos = (usize, usize);
+
+/// A step between neighboring positions as (dx, dy).
+pub type Dir = (isize, isize);
+
+/// Up, right, down and left.
+pub const DIRS4: [Dir; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// `DIRS4` and the diagonals, clockwise from up.
+pub const DIRS8: [Dir; 8] = [
+  (0, -1),
+  (1, -1),
+  (1, 0),
+  (1, 1),
+  (0, 1),
+  (-1, 1),
+  (-1, 0),
+  (-1, -1),
+];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+  width: usize,
+  height: usize,
+  cells: Vec<T>,
+}
+
+impl Grid<char> {
+  /// Parses one row per non-empty line of `input`, one cell per character.
+  pub fn parse(input: &str) -> Result<Self> {
+    let rows = parse::grid(input)?;
+    Ok(Self::from_rows(rows).expect("parsed grids are rectangular"))
+  }
+}
+
+impl<T> Grid<T> {
+  /// None if the rows are not all the same length.
+  pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+    let width = rows.first().map_or(0, Vec::len);
+    if rows.iter().any(|row| row.len() != width) {
+      return None;
+    }
+    let height = rows.len();
+    let cells = rows.into_iter().flatten().collect();
+    Some(Self {
+      width,
+      height,
+      cells,
+    })
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn get(&self, (x, y): Pos) -> Option<&T> {
+    if x < self.width && y < self.height {
+      Some(&self.cells[y * self.width + x])
+    } else {
+      None
+    }
+  }
+
+  pub fn get_mut(&mut self, (x, y): Pos) -> Option<&mut T> {
+    if x < self.width && y < self.height {
+      Some(&mut self.cells[y * self.width + x])
+    } else {
+      None
+    }
+  }
+
+  /// Like `get`, but the grid repeats endlessly to the right.
+  pub fn get_wrapping(&self, (x, y): Pos) -> Option<&T> {
+    if self.width == 0 {
+      return None;
+    }
+    self.get((x % self.width, y))
+  }
+
+  /// The position one `dir` step away from `pos`, if it is inside the grid.
+  pub fn step(&self, (x, y): Pos, (dx, dy): Dir) -> Option<Pos> {
+    let x = x.checked_add_signed(dx)?;
+    let y = y.checked_add_signed(dy)?;
+    if x < self.width && y < self.height {
+      Some((x, y))
+    } else {
+      None
+    }
+  }
+
+  /// The positions up, right, down and left of `pos` that are inside the grid.
+  pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    DIRS4.iter().filter_map(move |&dir| self.step(pos, dir))
+  }
+
+  /// The positions around `pos`, diagonals included, that are inside the grid.
+  pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    DIRS8.iter().filter_map(move |&dir| self.step(pos, dir))
+  }
+
+  /// The cells from `pos` in steps of `dir` until the edge of the grid, excluding `pos` itself.
+  /// A `(0, 0)` step never leaves `pos`, so its ray is empty.
+  pub fn ray(&self, pos: Pos, dir: Dir) -> impl Iterator<Item = (Pos, &T)> + '_ {
+    let mut pos = Some(pos).filter(|_| dir != (0, 0));
+    std::iter::from_fn(move || {
+      let next = self.step(pos?, dir)?;
+      pos = Some(next);
+      Some((next, &self.cells[next.1 * self.width + next.0]))
+    })
+  }
+
+  /// Every position, row by row.
+  pub fn positions(&self) -> impl Iterator<Item = Pos> {
+    let width = self.width;
+    (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+  }
+
+  pub fn row(&self, y: usize) -> &[T] {
+    &self.cells[y * self.width..(y + 1) * self.width]
+  }
+
+  pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+    (0..self.height).map(move |y| self.row(y))
+  }
+
+  /// Panics if `x` is outside the grid, like `row`.
+  pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+    assert!(x < self.width, "column {} outside a grid {} wide", x, self.width);
+    self.cells[x..].iter().step_by(self.width.max(1)).take(self.height)
+  }
+
+  pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+    (0..self.width).map(move |x| self.column(x))
+  }
+
+  pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+    Grid {
+      width: self.width,
+      height: self.height,
+      cells: self.cells.iter().map(f).collect(),
+    }
+  }
+}
+
+impl<T: Clone> Grid<T> {
+  /// The grid turned a quarter clockwise.
+  pub fn rotate(&self) -> Self {
+    let rows = (0..self.width)
+      .map(|x| (0..self.height).rev().map(|y| self.row(y)[x].clone()).collect())
+      .collect();
+    Self::with_rows(rows)
+  }
+
+  /// The grid mirrored left to right.
+  pub fn flip_horizontal(&self) -> Self {
+    let rows = self.rows().map(|row| row.iter().rev().cloned().collect()).collect();
+    Self::with_rows(rows)
+  }
+
+  /// The grid mirrored top to bottom.
+  pub fn flip_vertical(&self) -> Self {
+    let rows = self.rows().rev().map(<[T]>::to_vec).collect();
+    Self::with_rows(rows)
+  }
+
+  fn with_rows(rows: Vec<Vec<T>>) -> Self {
+    Self::from_rows(rows).expect("rows of a grid are rectangular")
+  }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for row in self.rows() {
+      for cell in row {
+        write!(f, "{}", cell)?;
+      }
+      writeln!(f)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::grid::Grid;
+
+  fn grid() -> Grid<char> {
+    Grid::parse("abc\ndef\n").unwrap()
+  }
+
+  #[test]
+  fn parse() {
+    let grid = grid();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.to_string(), "abc\ndef\n");
+    assert!(Grid::parse("ab\nc\n").is_err());
+    assert_eq!(Grid::from_rows(vec![vec![1], vec![2, 3]]), None);
+  }
+
+  #[test]
+  fn get() {
+    let mut grid = grid();
+    assert_eq!(grid.get((2, 1)), Some(&'f'));
+    assert_eq!(grid.get((3, 0)), None);
+    assert_eq!(grid.get((0, 2)), None);
+    assert_eq!(grid.get_wrapping((4, 1)), Some(&'e'));
+    assert_eq!(grid.get_wrapping((4, 2)), None);
+
+    *grid.get_mut((0, 0)).unwrap() = 'z';
+    assert_eq!(grid.row(0), &['z', 'b', 'c']);
+  }
+
+  #[test]
+  fn neighbors() {
+    let grid = grid();
+    let four: Vec<_> = grid.neighbors4((0, 0)).collect();
+    assert_eq!(four, vec![(1, 0), (0, 1)]);
+    let eight: Vec<_> = grid.neighbors8((1, 0)).collect();
+    assert_eq!(eight, vec![(2, 0), (2, 1), (1, 1), (0, 1), (0, 0)]);
+  }
+
+  #[test]
+  fn ray() {
+    let grid = Grid::parse("abc\ndef\nghi\n").unwrap();
+    let diagonal: String = grid.ray((0, 0), (1, 1)).map(|(_, c)| c).collect();
+    assert_eq!(diagonal, "ei");
+    let up: Vec<_> = grid.ray((2, 2), (0, -1)).map(|(pos, _)| pos).collect();
+    assert_eq!(up, vec![(2, 1), (2, 0)]);
+    assert_eq!(grid.ray((0, 0), (-1, 0)).count(), 0);
+    assert_eq!(grid.ray((1, 1), (0, 0)).count(), 0);
+  }
+
+  #[test]
+  fn rows_and_columns() {
+    let grid = grid();
+    let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+    assert_eq!(rows, vec!["abc", "def"]);
+    let columns: Vec<String> = grid.columns().map(|column| column.collect()).collect();
+    assert_eq!(columns, vec!["ad", "be", "cf"]);
+    assert_eq!(grid.positions().nth(4), Some((1, 1)));
+  }
+
+  #[test]
+  fn transform() {
+    let grid = grid();
+    assert_eq!(grid.rotate().to_string(), "da\neb\nfc\n");
+    assert_eq!(grid.rotate().rotate().rotate().rotate(), grid);
+    assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed\n");
+    assert_eq!(grid.flip_vertical().to_string(), "def\nabc\n");
+    assert_eq!(grid.map(|c| c.to_ascii_uppercase()).to_string(), "ABC\nDEF\n");
+  }
+}
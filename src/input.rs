@@ -0,0 +1,98 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::answers::{self, Answers, Entry};
+
+/// The path that stands for standard input.
+pub const STDIN: &str = "-";
+
+/// Where the runner reads a day's puzzle inputs from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+  /// Every input listed in the day's answers file.
+  Registered,
+  /// A single file, or standard input for `-`.
+  File(PathBuf),
+  /// `dayNN.txt` inside a directory.
+  Dir(PathBuf),
+}
+
+impl Source {
+  /// The inputs to solve `day` for. Inputs given on the command line are still checked against
+  /// the answers file, if it lists them.
+  pub fn entries(&self, day: u8) -> io::Result<Vec<Entry>> {
+    let file = match self {
+      Source::Registered => return answers::inputs_for(day),
+      Source::File(file) => file.clone(),
+      Source::Dir(dir) => dir.join(format!("day{:02}.txt", day)),
+    };
+
+    let known = Answers::load(&answers::answers_path(day))?
+      .and_then(|answers| answers.inputs.into_iter().find(|entry| entry.file == file));
+    Ok(vec![known.unwrap_or_else(|| Entry::unknown(file))])
+  }
+}
+
+/// Reads the puzzle input at `path`, or from standard input if `path` is `-`. Unless `raw`, the
+/// input is normalized so that every day receives the same clean text.
+pub fn read(path: &Path, raw: bool) -> io::Result<String> {
+  let input = if path == Path::new(STDIN) {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    input
+  } else {
+    fs::read_to_string(path)?
+  };
+  Ok(if raw { input } else { normalize(&input) })
+}
+
+/// Strips a byte order mark, turns `\r\n` and `\r` line endings into `\n`, and trims trailing
+/// whitespace from every line. Trailing blank lines are dropped, and non-empty input ends with a
+/// single newline.
+pub fn normalize(input: &str) -> String {
+  let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+  let input = input.replace("\r\n", "\n").replace('\r', "\n");
+
+  let mut normalized = String::with_capacity(input.len());
+  for line in input.lines() {
+    normalized.push_str(line.trim_end());
+    normalized.push('\n');
+  }
+  let len = normalized.trim_end().len();
+  normalized.truncate(len);
+  if !normalized.is_empty() {
+    normalized.push('\n');
+  }
+  normalized
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use crate::answers::Entry;
+  use crate::input::{self, Source};
+
+  #[test]
+  fn entries() {
+    let registered = Source::Registered.entries(1).unwrap();
+    assert_eq!(registered[0].file, PathBuf::from("inputs/day01.txt"));
+    assert!(registered[0].part1.is_some());
+
+    // Known inputs keep their answers.
+    let file = Source::File("inputs/day01.txt".into()).entries(1).unwrap();
+    assert_eq!(file, registered[..1]);
+
+    let dir = Source::Dir("elsewhere".into()).entries(1).unwrap();
+    assert_eq!(dir, vec![Entry::unknown("elsewhere/day01.txt")]);
+  }
+
+  #[test]
+  fn normalize() {
+    assert_eq!(input::normalize("\u{feff}a \r\nb\t\r\n\r\nc"), "a\nb\n\nc\n");
+    assert_eq!(input::normalize("a\rb\n\n  \n\n"), "a\nb\n");
+    assert_eq!(input::normalize("  x\n"), "  x\n");
+    assert_eq!(input::normalize(" \r\n"), "");
+  }
+}
@@ -1,3 +1,5 @@
+pub mod answers;
+pub mod bench;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -23,4 +25,19 @@ pub mod day09;
 // pub mod day23;
 // pub mod day24;
 // pub mod day25;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod log;
+pub mod parse;
 pub mod problem;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod schema;
+pub mod validation;
+pub mod watch;
+
+/// The Advent of Code year solved by this crate.
+pub const YEAR: u16 = 2020;
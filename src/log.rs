@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// How much diagnostic output to emit, from least to most.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+  Info,
+  Debug,
+  Trace,
+}
+
+impl Level {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Level::Info => "info",
+      Level::Debug => "debug",
+      Level::Trace => "trace",
+    }
+  }
+}
+
+impl FromStr for Level {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, String> {
+    match s {
+      "info" => Ok(Level::Info),
+      "debug" => Ok(Level::Debug),
+      "trace" => Ok(Level::Trace),
+      _ => Err(format!("Unknown log level {:?}", s)),
+    }
+  }
+}
+
+/// The logging context handed to each part. Messages go to stderr, so they never mix with the
+/// answers on stdout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Log {
+  day: u8,
+  level: Option<Level>,
+}
+
+impl Log {
+  /// Logs messages for `day` up to `level`, or nothing if it is None.
+  pub fn new(day: u8, level: Option<Level>) -> Self {
+    Self { day, level }
+  }
+
+  /// Discards every message.
+  pub fn off() -> Self {
+    Self::new(0, None)
+  }
+
+  pub fn enabled(&self, level: Level) -> bool {
+    Some(level) <= self.level
+  }
+
+  /// Prints `args` if `level` is enabled. Formatting is skipped otherwise, so it is cheap to
+  /// call in hot loops.
+  pub fn log(&self, level: Level, args: fmt::Arguments) {
+    if self.enabled(level) {
+      eprintln!("[day{:02} {}] {}", self.day, level.as_str(), args);
+    }
+  }
+
+  pub fn info(&self, args: fmt::Arguments) {
+    self.log(Level::Info, args);
+  }
+
+  pub fn debug(&self, args: fmt::Arguments) {
+    self.log(Level::Debug, args);
+  }
+
+  pub fn trace(&self, args: fmt::Arguments) {
+    self.log(Level::Trace, args);
+  }
+}
+
+/// Which level to log at for each day, built from `-v` options such as `debug` (every day) or
+/// `day03=trace` (a single day).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+  default: Option<Level>,
+  days: HashMap<u8, Level>,
+}
+
+impl Filter {
+  /// Applies one directive on top of the ones before it.
+  pub fn add(&mut self, directive: &str) -> Result<(), String> {
+    match directive.split_once('=') {
+      Some((day, level)) => {
+        let day = day.trim_start_matches("day");
+        let day = match day.parse::<u8>() {
+          Ok(day) if (1..=25).contains(&day) => day,
+          _ => return Err(format!("Invalid day in log directive {:?}", directive)),
+        };
+        self.days.insert(day, level.parse()?);
+      }
+      None => self.default = Some(directive.parse()?),
+    }
+    Ok(())
+  }
+
+  pub fn level(&self, day: u8) -> Option<Level> {
+    self.days.get(&day).copied().or(self.default)
+  }
+
+  pub fn log(&self, day: u8) -> Log {
+    Log::new(day, self.level(day))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::log::{Filter, Level, Log};
+
+  #[test]
+  fn enabled() {
+    let log = Log::new(3, Some(Level::Debug));
+    assert!(log.enabled(Level::Info));
+    assert!(log.enabled(Level::Debug));
+    assert!(!log.enabled(Level::Trace));
+    assert!(!Log::off().enabled(Level::Info));
+  }
+
+  #[test]
+  fn filter() {
+    let mut filter = Filter::default();
+    assert_eq!(filter.level(3), None);
+
+    filter.add("day03=trace").unwrap();
+    filter.add("info").unwrap();
+    filter.add("7=debug").unwrap();
+    assert_eq!(filter.level(3), Some(Level::Trace));
+    assert_eq!(filter.level(7), Some(Level::Debug));
+    assert_eq!(filter.level(1), Some(Level::Info));
+
+    assert!(filter.add("day03=loud").is_err());
+    assert!(filter.add("day30=info").is_err());
+    assert!(filter.add("verbose").is_err());
+  }
+}
@@ -1,157 +1,938 @@
-use std::fs;
+use std::env;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
 
-use clap::{App, Arg};
+use clap::{App, Arg, ArgMatches, SubCommand};
 use colored::*;
 
-use aoc2020::day01::DayOne;
-use aoc2020::day02::DayTwo;
-use aoc2020::day03::DayThree;
+use aoc2020::answers::{self, Entry};
+use aoc2020::bench;
+use aoc2020::day01::{self, Algo, DayOne};
 use aoc2020::day04::DayFour;
-use aoc2020::day05::DayFive;
-use aoc2020::day06::DaySix;
-use aoc2020::day07::DaySeven;
-use aoc2020::day08::DayEight;
-use aoc2020::day09::DayNine;
-// use aoc2020::day10::DayTen;
-// use aoc2020::day11::DayEleven;
-// use aoc2020::day12::DayTwelve;
-// use aoc2020::day13::DayThirteen;
-// use aoc2020::day14::DayFourteen;
-// use aoc2020::day15::DayFifteen;
-// use aoc2020::day16::DaySixteen;
-// use aoc2020::day17::DaySeventeen;
-// use aoc2020::day18::DayEighteen;
-// use aoc2020::day19::DayNineteen;
-// use aoc2020::day20::DayTwenty;
-// use aoc2020::day21::DayTwentyOne;
-// use aoc2020::day22::DayTwentyTwo;
-// use aoc2020::day23::DayTwentyThree;
-// use aoc2020::day24::DayTwentyFour;
-// use aoc2020::day25::DayTwentyFive;
-use aoc2020::problem::Problem;
+use aoc2020::error::Error;
+use aoc2020::input::{self, Source};
+use aoc2020::log::{self, Log};
+use aoc2020::problem::{self, Problem};
+use aoc2020::registry;
+use aoc2020::report::{self, Format, Record, Status, Summary};
+use aoc2020::runner::{self, Outcome, Task, Timings};
+use aoc2020::scaffold;
+use aoc2020::schema::Schema;
+use aoc2020::validation;
+use aoc2020::watch::{self, Snapshot};
+use aoc2020::YEAR;
 
 fn main() -> Result<(), String> {
   let matches = App::new("AoC 2020")
-    .arg(Arg::with_name("debug").short("d").long("debug"))
-    .arg(Arg::with_name("DAY").index(1))
+    .arg(
+      Arg::with_name("verbose")
+        .short("v")
+        .long("verbose")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .help("Logs to stderr at info, debug or trace, for every day or e.g. day03=trace"),
+    )
+    .arg(
+      Arg::with_name("time")
+        .short("t")
+        .long("time")
+        .help("Reports wall time for reading, parsing and each part"),
+    )
+    .arg(
+      Arg::with_name("jobs")
+        .short("j")
+        .long("jobs")
+        .takes_value(true)
+        .default_value("1")
+        .help("Parses inputs and solves parts on at most this many threads"),
+    )
+    .arg(
+      Arg::with_name("part")
+        .short("p")
+        .long("part")
+        .takes_value(true)
+        .possible_values(&["1", "2"])
+        .help("Runs only this part"),
+    )
+    .arg(
+      Arg::with_name("quiet")
+        .short("q")
+        .long("quiet")
+        .conflicts_with_all(&["time", "format"])
+        .help("Prints only the answers, one per line"),
+    )
+    .arg(
+      Arg::with_name("format")
+        .short("f")
+        .long("format")
+        .takes_value(true)
+        .possible_values(&["text", "json", "tsv", "junit"])
+        .default_value("text"),
+    )
+    .arg(
+      Arg::with_name("input")
+        .short("i")
+        .long("input")
+        .takes_value(true)
+        .requires("DAY")
+        .conflicts_with("input-dir")
+        .help("Input file for the day, or - to read it from stdin"),
+    )
+    .arg(
+      Arg::with_name("input-dir")
+        .long("input-dir")
+        .takes_value(true)
+        .help("Directory to read dayNN.txt inputs from"),
+    )
+    .arg(
+      Arg::with_name("raw")
+        .long("raw")
+        .help("Keeps BOMs, CRLF line endings and trailing whitespace in the inputs"),
+    )
+    .arg(
+      Arg::with_name("DAY")
+        .index(1)
+        .help("Day to run, a range like 3-7 or a list like 1,4,9 [default: all]"),
+    )
+    .subcommand(
+      SubCommand::with_name("bench")
+        .about("Runs each day repeatedly and reports timing statistics")
+        .arg(
+          Arg::with_name("DAY")
+            .index(1)
+            .help("Day to benchmark, a range like 3-7 or a list like 1,4,9 [default: all]"),
+        )
+        .arg(
+          Arg::with_name("iterations")
+            .short("n")
+            .long("iterations")
+            .takes_value(true)
+            .default_value("100"),
+        )
+        .arg(
+          Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .takes_value(true)
+            .default_value("bench.json")
+            .help("File to write the results to, as JSON"),
+        )
+        .arg(
+          Arg::with_name("baseline")
+            .long("baseline")
+            .takes_value(true)
+            .help("Results of an earlier run to compare against"),
+        )
+        .arg(
+          Arg::with_name("threshold")
+            .long("threshold")
+            .takes_value(true)
+            .default_value("10")
+            .help("Slowdown of a median, in percent, that counts as a regression"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name("day01")
+        .about("Finds k numbers in day 1's input that add up to a target")
+        .arg(
+          Arg::with_name("k")
+            .short("k")
+            .long("k")
+            .takes_value(true)
+            .default_value("2")
+            .help("How many numbers to add up"),
+        )
+        .arg(
+          Arg::with_name("target")
+            .long("target")
+            .takes_value(true)
+            .default_value("2020"),
+        )
+        .arg(
+          Arg::with_name("all")
+            .long("all")
+            .conflicts_with("algo")
+            .help("Lists every combination instead of just the first"),
+        )
+        .arg(
+          Arg::with_name("algo")
+            .long("algo")
+            .takes_value(true)
+            .possible_values(&["hash", "two-pointer", "bitset", "brute-force", "all"])
+            .help("Strategy to search with, or all to compare them [default: hash]"),
+        )
+        .arg(
+          Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .takes_value(true)
+            .default_value("inputs/day01.txt")
+            .help("Input file, or - to read it from stdin"),
+        )
+        .arg(
+          Arg::with_name("generate")
+            .long("generate")
+            .takes_value(true)
+            .conflicts_with("input")
+            .help("Searches this many generated numbers instead of an input file"),
+        )
+        .arg(
+          Arg::with_name("max")
+            .long("max")
+            .takes_value(true)
+            .requires("generate")
+            .help("Largest generated number [default: 2020]"),
+        )
+        .arg(
+          Arg::with_name("seed")
+            .long("seed")
+            .takes_value(true)
+            .requires("generate")
+            .help("Seed for the generated numbers [default: 1]"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name("day04")
+        .about("Prints day 4's passports as JSON lines, typed and judged by both parts")
+        .arg(
+          Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .takes_value(true)
+            .default_value("inputs/day04.txt")
+            .help("Input file, or - to read it from stdin"),
+        )
+        .arg(
+          Arg::with_name("schema")
+            .long("schema")
+            .takes_value(true)
+            .help("Judges passports against this TOML schema instead of the puzzle's rules"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name("new")
+        .about("Creates the module, input and example files for a new day")
+        .arg(Arg::with_name("DAY").index(1).required(true)),
+    )
+    .subcommand(
+      SubCommand::with_name("validate")
+        .about("Lists each record of a day's input as valid or invalid, with the rule it broke")
+        .arg(Arg::with_name("DAY").index(1).required(true))
+        .arg(
+          Arg::with_name("part")
+            .short("p")
+            .long("part")
+            .takes_value(true)
+            .possible_values(&["1", "2"])
+            .help("Judges records by this part's rules only"),
+        )
+        .arg(
+          Arg::with_name("format")
+            .short("f")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["text", "json"])
+            .default_value("text"),
+        )
+        .arg(
+          Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .takes_value(true)
+            .help("Input file, or - to read it from stdin [default: inputs/dayNN.txt]"),
+        )
+        .arg(
+          Arg::with_name("raw")
+            .long("raw")
+            .help("Validates the input exactly as read, without normalizing it"),
+        )
+        .arg(
+          Arg::with_name("schema")
+            .long("schema")
+            .takes_value(true)
+            .help("Day 4 only: checks passports against this TOML schema instead"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name("watch")
+        .about("Rebuilds and re-runs a day whenever its source, input or examples change")
+        .arg(Arg::with_name("DAY").index(1).required(true))
+        .arg(
+          Arg::with_name("interval")
+            .long("interval")
+            .takes_value(true)
+            .default_value("500")
+            .help("Milliseconds between checks for changes"),
+        ),
+    )
     .get_matches();
-  let debug = matches.is_present("debug");
 
-  match matches.value_of("DAY") {
-    Some(day) => {
-      print_problem(day.parse::<usize>().expect("Invalid day input!"), debug)?;
+  if let Some(matches) = matches.subcommand_matches("bench") {
+    return run_bench(matches);
+  }
+  if let Some(matches) = matches.subcommand_matches("day01") {
+    return run_day01(matches);
+  }
+  if let Some(matches) = matches.subcommand_matches("day04") {
+    return run_day04(matches);
+  }
+  if let Some(matches) = matches.subcommand_matches("new") {
+    return run_new(matches);
+  }
+  if let Some(matches) = matches.subcommand_matches("validate") {
+    return run_validate(matches);
+  }
+  if let Some(matches) = matches.subcommand_matches("watch") {
+    return run_watch(matches);
+  }
+
+  let mut log = log::Filter::default();
+  for directive in matches.values_of("verbose").into_iter().flatten() {
+    log.add(directive)?;
+  }
+
+  let options = Options {
+    log,
+    time: matches.is_present("time"),
+    quiet: matches.is_present("quiet"),
+    raw: matches.is_present("raw"),
+    jobs: match matches.value_of("jobs").unwrap().parse() {
+      Ok(jobs) if jobs > 0 => jobs,
+      _ => return Err("Invalid number of jobs!".to_string()),
+    },
+    part: matches.value_of("part").map(|part| part.parse().unwrap()),
+    format: matches.value_of("format").unwrap().parse()?,
+    source: match (matches.value_of("input"), matches.value_of("input-dir")) {
+      (Some(file), _) => Source::File(file.into()),
+      (None, Some(dir)) => Source::Dir(dir.into()),
+      (None, None) => Source::Registered,
+    },
+  };
+  let mut records = Vec::new();
+
+  let days = match matches.value_of("DAY") {
+    Some(days) => runner::parse_days(days)?,
+    None => (1..=25).collect(),
+  };
+  let mut print = |idx: usize, day: u8, run: &DayRun| {
+    if idx > 0 && options.format == Format::Text && !options.quiet {
+      println!();
     }
-    None => {
-      for day in 1..25 {
-        if print_problem(day, debug).is_err() {
-          break;
-        }
-        println!();
-      }
+    print_problem(day, run, &options, &mut records);
+  };
+  if options.jobs > 1 {
+    // Solve everything first, then print in day order.
+    let runs = run_problems(&days, &options);
+    for (idx, (&day, run)) in days.iter().zip(&runs).enumerate() {
+      print(idx, day, run);
+    }
+  } else {
+    for (idx, &day) in days.iter().enumerate() {
+      print(idx, day, &run_problem(day, &options));
     }
   }
 
+  let summary = Summary::of(&records);
+  if options.format == Format::Text {
+    if days.len() > 1 && !options.quiet {
+      println!();
+      print_summary(&records, &summary);
+    }
+  } else {
+    report::write(options.format, &records, &mut io::stdout())
+      .map_err(|err| format!("Could not write report: {}", err))?;
+  }
+
+  if !summary.is_success() {
+    return Err(format!(
+      "{} part(s) failed, {} part(s) errored",
+      summary.fail, summary.error
+    ));
+  }
   Ok(())
 }
 
-fn print_problem(day: usize, debug: bool) -> Result<(), String> {
-  let day_str = format!("{:02}", day);
+struct Options {
+  log: log::Filter,
+  time: bool,
+  /// Print only the raw answers, with anything else going to stderr.
+  quiet: bool,
+  /// Pass inputs to the days exactly as read, without normalizing them.
+  raw: bool,
+  /// Number of threads to parse inputs and solve parts on.
+  jobs: usize,
+  /// The only part to run, or None for both.
+  part: Option<u8>,
+  format: Format,
+  source: Source,
+}
+
+/// A day's outcomes, gathered before anything is printed so that days can be solved in parallel.
+enum DayRun {
+  /// The day is not implemented.
+  Skipped,
+  /// The day's inputs could not be listed.
+  Failed(Error),
+  Solved(Vec<(Entry, Outcome)>),
+}
+
+/// A day's problem and its inputs, read but not solved yet.
+struct Loaded {
+  problem: Box<dyn problem::Solver>,
+  log: Log,
+  /// Each input with its contents, or why they could not be read, and how long reading took.
+  inputs: Vec<(Entry, Result<String, Error>, Duration)>,
+}
+
+/// Builds `day` and reads its inputs, or returns how the day ended if it cannot be solved.
+fn load(day: u8, options: &Options) -> Result<Loaded, DayRun> {
+  let problem = match registry::get(YEAR, day) {
+    Some(reg) => reg.build(),
+    None => return Err(DayRun::Skipped),
+  };
+  let entries = match options.source.entries(day) {
+    Ok(entries) => entries,
+    Err(err) => {
+      let path = answers::answers_path(day);
+      return Err(DayRun::Failed(Error::Input(format!("{}: {}", path.display(), err))));
+    }
+  };
+
+  let inputs = entries
+    .into_iter()
+    .map(|entry| {
+      let (input, read_time) = runner::time(|| input::read(&entry.file, options.raw));
+      let input = input.map_err(|err| Error::Input(format!("{}: {}", entry.file.display(), err)));
+      (entry, input, read_time)
+    })
+    .collect();
+  Ok(Loaded {
+    problem,
+    log: options.log.log(day),
+    inputs,
+  })
+}
 
-  let problem =
-    get_problem(day, debug).ok_or_else(|| format!("Day {} not implemented!", day_str))?;
-  let input = fs::read_to_string(format!("inputs/day{}.txt", day_str)).unwrap();
+/// Solves the selected parts of `day` for each of its inputs.
+fn run_problem(day: u8, options: &Options) -> DayRun {
+  let Loaded {
+    problem,
+    log,
+    inputs,
+  } = match load(day, options) {
+    Ok(loaded) => loaded,
+    Err(run) => return run,
+  };
 
-  println!("{}", format!("DAY {}", day_str).blue().bold());
-  print_part(1, &problem.soln_one(), &problem.part_one(&input));
-  print_part(2, &problem.soln_two(), &problem.part_two(&input));
+  let outcomes = inputs
+    .into_iter()
+    .map(|(entry, input, read_time)| {
+      let mut outcome = match input {
+        Ok(input) => runner::solve(problem.as_ref(), &input, options.part, &log),
+        Err(err) => Outcome::failed(err, options.part),
+      };
+      outcome.timings.read = read_time;
+      (entry, outcome)
+    })
+    .collect();
+  DayRun::Solved(outcomes)
+}
 
-  Ok(())
+/// Like `run_problem` for every day, with the parts of all inputs of all days sharing
+/// `options.jobs` threads.
+fn run_problems(days: &[u8], options: &Options) -> Vec<DayRun> {
+  let loaded: Vec<_> = days.iter().map(|&day| load(day, options)).collect();
+
+  let tasks: Vec<Task> = loaded
+    .iter()
+    .flatten()
+    .flat_map(|loaded| {
+      loaded.inputs.iter().filter_map(move |(_, input, _)| {
+        input.as_ref().ok().map(|input| Task {
+          solver: loaded.problem.as_ref(),
+          input,
+          log: loaded.log,
+        })
+      })
+    })
+    .collect();
+  let mut outcomes = runner::solve_parallel(&tasks, options.part, options.jobs).into_iter();
+
+  loaded
+    .into_iter()
+    .map(|loaded| {
+      let loaded = match loaded {
+        Ok(loaded) => loaded,
+        Err(run) => return run,
+      };
+      let outcomes = loaded
+        .inputs
+        .into_iter()
+        .map(|(entry, input, read_time)| {
+          let mut outcome = match input {
+            Ok(_) => outcomes.next().expect("every input is solved"),
+            Err(err) => Outcome::failed(err, options.part),
+          };
+          outcome.timings.read = read_time;
+          (entry, outcome)
+        })
+        .collect();
+      DayRun::Solved(outcomes)
+    })
+    .collect()
 }
 
-fn print_part(num: usize, expected: &Option<String>, actual: &Option<String>) {
+/// Prints the outcomes of `day` as text and adds them to `records`. Days that are not
+/// implemented are reported as skipped.
+fn print_problem(day: u8, run: &DayRun, options: &Options, records: &mut Vec<Record>) {
+  let header = format!("DAY {:02}", day).blue().bold();
+
+  let outcomes = match run {
+    DayRun::Skipped => {
+      if options.quiet {
+        eprintln!("Day {:02} skipped (not implemented)", day);
+      } else if options.format == Format::Text {
+        println!("{} {}", header, "skipped (not implemented)".yellow());
+      }
+      let entry = Entry::unknown(answers::default_input(day));
+      let outcome = Outcome::failed(Error::Unimplemented, options.part);
+      push_records(records, day, &entry, &outcome);
+      return;
+    }
+    DayRun::Failed(err) => {
+      if options.quiet {
+        eprintln!("Day {:02}: {}", day, err);
+      } else if options.format == Format::Text {
+        println!("{} {}", header, err.to_string().red());
+      }
+      let entry = Entry::unknown(answers::default_input(day));
+      let outcome = Outcome::failed(err.clone(), options.part);
+      push_records(records, day, &entry, &outcome);
+      return;
+    }
+    DayRun::Solved(outcomes) => outcomes,
+  };
+
+  for (idx, (entry, outcome)) in outcomes.iter().enumerate() {
+    if options.quiet {
+      print_answer(day, 1, &outcome.part_one);
+      print_answer(day, 2, &outcome.part_two);
+    } else if options.format == Format::Text {
+      if idx > 0 {
+        println!();
+      }
+      println!("{} ({})", header, entry.file.display());
+      if let Some(part_one) = &outcome.part_one {
+        print_part(1, &entry.part1, part_one);
+      }
+      if let Some(part_two) = &outcome.part_two {
+        print_part(2, &entry.part2, part_two);
+      }
+      if options.time {
+        print_timings(&outcome.timings);
+      }
+    }
+
+    push_records(records, day, entry, outcome);
+  }
+}
+
+fn push_records(records: &mut Vec<Record>, day: u8, entry: &Entry, outcome: &Outcome) {
+  let path = entry.file.display().to_string();
+  let timings = &outcome.timings;
+  let parts = [
+    (1, &outcome.part_one, timings.part_one),
+    (2, &outcome.part_two, timings.part_two),
+  ];
+  for (num, actual, duration) in parts {
+    if let Some(actual) = actual {
+      let expected = entry.expected(num).cloned();
+      records.push(Record::new(YEAR, day, &path, num, expected, actual, duration));
+    }
+  }
+}
+
+/// Prints just the answer to a part that was run, or its error on stderr.
+fn print_answer(day: u8, num: usize, actual: &Option<Result<String, Error>>) {
+  match actual {
+    Some(Ok(answer)) => println!("{}", answer),
+    Some(Err(err)) => eprintln!("Day {:02} part {}: {}", day, num, err),
+    None => {}
+  }
+}
+
+fn print_part(num: usize, expected: &Option<String>, actual: &Result<String, Error>) {
   println!(
     "Part {}: (expected answer: {})",
     num,
     expected.as_ref().unwrap_or(&"unknown".to_string()).bold()
   );
+  match actual {
+    Ok(answer) => println!("Actual: {} {}", answer.bold(), result(expected, answer)),
+    Err(Error::Unimplemented) => println!("Actual: {}", "unimplemented".yellow()),
+    Err(Error::NoSolution) => println!("Actual: {} {}", "no solution".bold(), "✗".red()),
+    Err(err) => println!("Actual: {} {}", err.to_string().red(), "✗".red()),
+  }
+}
+
+fn result(expected: &Option<String>, actual: &str) -> String {
+  match expected {
+    None => "??".yellow().to_string(),
+    Some(expected) if expected == actual => "✓".green().to_string(),
+    Some(_) => "✗".red().to_string(),
+  }
+}
+
+fn print_summary(records: &[Record], summary: &Summary) {
+  fn status(status: Status) -> ColoredString {
+    let text = format!("{:<13}", status.as_str());
+    match status {
+      Status::Pass => text.green(),
+      Status::Fail | Status::Error => text.red(),
+      Status::Unknown | Status::Unimplemented => text.yellow(),
+    }
+  }
+
+  println!("{}", "SUMMARY".blue().bold());
+  println!("{:<4} {:<13} {:<13} Input", "Day", "Part 1", "Part 2");
+  for run in records.chunk_by(|a, b| a.day == b.day && a.input == b.input) {
+    let part = |num| match run.iter().find(|record| record.part == num) {
+      Some(record) => status(record.status),
+      None => format!("{:<13}", "-").normal(),
+    };
+    println!(
+      "{:<4} {} {} {}",
+      format!("{:02}", run[0].day),
+      part(1),
+      part(2),
+      run[0].input
+    );
+  }
+  println!(
+    "Passed: {}  Failed: {}  Unknown: {}  Skipped: {}  Errors: {}",
+    summary.pass.to_string().green(),
+    summary.fail.to_string().red(),
+    summary.unknown.to_string().yellow(),
+    summary.unimplemented.to_string().yellow(),
+    summary.error.to_string().red()
+  );
+}
+
+fn print_timings(timings: &Timings) {
   println!(
-    "Actual: {} {}",
-    actual
-      .as_ref()
-      .unwrap_or(&"unimplemented".to_string())
-      .bold(),
-    result(&expected, &actual)
+    "Time: read {:.2?}, parse {:.2?}, part 1 {:.2?}, part 2 {:.2?}",
+    timings.read, timings.parse, timings.part_one, timings.part_two
   );
 }
 
-fn result(expected: &Option<String>, actual: &Option<String>) -> String {
-  if expected.is_none() {
-    "??".yellow().to_string()
-  } else if expected == actual {
-    "✓".green().to_string()
-  } else {
-    "✗".red().to_string()
-  }
-}
-
-fn get_problem(day: usize, debug: bool) -> Option<Box<dyn Problem>> {
-  if debug {
-    match day {
-      1 => Some(Box::new(DayOne::debug())),
-      2 => Some(Box::new(DayTwo::debug())),
-      3 => Some(Box::new(DayThree::debug())),
-      4 => Some(Box::new(DayFour::debug())),
-      5 => Some(Box::new(DayFive::debug())),
-      6 => Some(Box::new(DaySix::debug())),
-      7 => Some(Box::new(DaySeven::debug())),
-      8 => Some(Box::new(DayEight::debug())),
-      9 => Some(Box::new(DayNine::debug())),
-      // 10 => Some(Box::new(DayTen::debug())),
-      // 11 => Some(Box::new(DayEleven::debug())),
-      // 12 => Some(Box::new(DayTwelve::debug())),
-      // 13 => Some(Box::new(DayThirteen::debug())),
-      // 14 => Some(Box::new(DayFourteen::debug())),
-      // 15 => Some(Box::new(DayFifteen::debug())),
-      // 16 => Some(Box::new(DaySixteen::debug())),
-      // 17 => Some(Box::new(DaySeventeen::debug())),
-      // 18 => Some(Box::new(DayEighteen::debug())),
-      // 19 => Some(Box::new(DayNineteen::debug())),
-      // 20 => Some(Box::new(DayTwenty::debug())),
-      // 21 => Some(Box::new(DayTwentyOne::debug())),
-      // 22 => Some(Box::new(DayTwentyTwo::debug())),
-      // 23 => Some(Box::new(DayTwentyThree::debug())),
-      // 24 => Some(Box::new(DayTwentyFour::debug())),
-      // 25 => Some(Box::new(DayTwentyFive::debug())),
-      _ => None,
+fn run_bench(matches: &ArgMatches) -> Result<(), String> {
+  let iterations: usize = matches
+    .value_of("iterations")
+    .unwrap()
+    .parse()
+    .map_err(|_| "Invalid number of iterations!".to_string())?;
+  let threshold: f64 = matches
+    .value_of("threshold")
+    .unwrap()
+    .parse()
+    .map_err(|_| "Invalid threshold!".to_string())?;
+  let output = Path::new(matches.value_of("output").unwrap());
+
+  let registrations = match matches.value_of("DAY") {
+    Some(day) => {
+      let days = runner::parse_days(day)?;
+      days.into_iter().filter_map(|day| registry::get(YEAR, day)).collect()
     }
-  } else {
-    match day {
-      1 => Some(Box::new(DayOne::new())),
-      2 => Some(Box::new(DayTwo::new())),
-      3 => Some(Box::new(DayThree::new())),
-      4 => Some(Box::new(DayFour::new())),
-      5 => Some(Box::new(DayFive::new())),
-      6 => Some(Box::new(DaySix::new())),
-      7 => Some(Box::new(DaySeven::new())),
-      8 => Some(Box::new(DayEight::new())),
-      9 => Some(Box::new(DayNine::new())),
-      // 10 => Some(Box::new(DayTen::new())),
-      // 11 => Some(Box::new(DayEleven::new())),
-      // 12 => Some(Box::new(DayTwelve::new())),
-      // 13 => Some(Box::new(DayThirteen::new())),
-      // 14 => Some(Box::new(DayFourteen::new())),
-      // 15 => Some(Box::new(DayFifteen::new())),
-      // 16 => Some(Box::new(DaySixteen::new())),
-      // 17 => Some(Box::new(DaySeventeen::new())),
-      // 18 => Some(Box::new(DayEighteen::new())),
-      // 19 => Some(Box::new(DayNineteen::new())),
-      // 20 => Some(Box::new(DayTwenty::new())),
-      // 21 => Some(Box::new(DayTwentyOne::new())),
-      // 22 => Some(Box::new(DayTwentyTwo::new())),
-      // 23 => Some(Box::new(DayTwentyThree::new())),
-      // 24 => Some(Box::new(DayTwentyFour::new())),
-      // 25 => Some(Box::new(DayTwentyFive::new())),
-      _ => None,
+    None => registry::for_year(YEAR),
+  };
+
+  let mut results = Vec::new();
+  for reg in registrations {
+    let entries = answers::inputs_for(reg.day)
+      .map_err(|err| format!("Could not load answers for day {:02}: {}", reg.day, err))?;
+    let solver = reg.build();
+
+    println!("{}", format!("DAY {:02}", reg.day).blue().bold());
+    let input = match input::read(&entries[0].file, false) {
+      Ok(input) => input,
+      Err(err) => {
+        let err = Error::Input(format!("{}: {}", entries[0].file.display(), err));
+        println!("{}", err.to_string().red());
+        continue;
+      }
+    };
+    match bench::bench(solver.as_ref(), &input, reg.year, reg.day, iterations) {
+      Ok(result) => {
+        for (stage, stats) in result.stages().iter() {
+          println!(
+            "{:>7}: min {:>10.2?}  median {:>10.2?}  mean {:>10.2?}  p95 {:>10.2?}",
+            stage,
+            Duration::from_nanos(stats.min_ns),
+            Duration::from_nanos(stats.median_ns),
+            Duration::from_nanos(stats.mean_ns),
+            Duration::from_nanos(stats.p95_ns),
+          );
+        }
+        results.push(result);
+      }
+      Err(err) => println!("{}", err.to_string().red()),
+    }
+  }
+
+  bench::save(output, &results)
+    .map_err(|err| format!("Could not write {}: {}", output.display(), err))?;
+  println!("Results written to {}", output.display());
+
+  if let Some(baseline) = matches.value_of("baseline") {
+    let baseline = bench::load(Path::new(baseline))
+      .map_err(|err| format!("Could not read baseline {}: {}", baseline, err))?;
+    let regressions = bench::regressions(&baseline, &results, threshold);
+    for regression in &regressions {
+      println!(
+        "{}",
+        format!(
+          "Regression: day {:02} {} median {:.2?} -> {:.2?} (+{:.1}%)",
+          regression.day,
+          regression.stage,
+          Duration::from_nanos(regression.baseline_ns),
+          Duration::from_nanos(regression.current_ns),
+          regression.slowdown()
+        )
+        .red()
+      );
+    }
+    if !regressions.is_empty() {
+      return Err(format!(
+        "{} regression(s) over {}%",
+        regressions.len(),
+        threshold
+      ));
+    }
+  }
+
+  Ok(())
+}
+
+fn run_day01(matches: &ArgMatches) -> Result<(), String> {
+  let k: usize = matches
+    .value_of("k")
+    .unwrap()
+    .parse()
+    .map_err(|_| "Invalid k!".to_string())?;
+  let target: i32 = matches
+    .value_of("target")
+    .unwrap()
+    .parse()
+    .map_err(|_| "Invalid target!".to_string())?;
+  let nums = match matches.value_of("generate") {
+    Some(len) => {
+      let len: usize = len.parse().map_err(|_| "Invalid count!".to_string())?;
+      let max: i32 = matches
+        .value_of("max")
+        .unwrap_or("2020")
+        .parse()
+        .map_err(|_| "Invalid max!".to_string())?;
+      let seed: u64 = matches
+        .value_of("seed")
+        .unwrap_or("1")
+        .parse()
+        .map_err(|_| "Invalid seed!".to_string())?;
+      day01::generate(len, max, seed)
+    }
+    None => {
+      let path = Path::new(matches.value_of("input").unwrap());
+      let input = input::read(path, false)
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+      DayOne::new()
+        .parse(&input)
+        .map_err(|err| format!("{}: {}", path.display(), err))?
+    }
+  };
+  let print = |indices: &[usize]| {
+    let terms: Vec<String> = indices.iter().map(|&idx| nums[idx].to_string()).collect();
+    let product = match day01::product(&nums, indices) {
+      Some(product) => product.to_string().bold(),
+      None => "overflow".red(),
+    };
+    println!("{} = {} (product {})", terms.join(" + "), target, product);
+  };
+  let none = || format!("No {} numbers add up to {}", k, target);
+
+  let algos = match matches.value_of("algo") {
+    None => {
+      let limit = if matches.is_present("all") { usize::MAX } else { 1 };
+      let mut found = 0;
+      for indices in day01::k_sums(&nums, k, target).take(limit) {
+        print(&indices);
+        found += 1;
+      }
+      return if found == 0 { Err(none()) } else { Ok(()) };
+    }
+    Some("all") => Algo::ALL.to_vec(),
+    Some(algo) => vec![algo.parse::<Algo>()?],
+  };
+
+  let mut found = false;
+  let mut searched = false;
+  for algo in algos {
+    if let Some(reason) = algo.skip_reason(&nums) {
+      println!("{:<12} {}", algo.as_str(), format!("({}, skipped)", reason).yellow());
+      continue;
+    }
+    searched = true;
+    let (indices, elapsed) = runner::time(|| algo.k_sum(&nums, k, target));
+    print!("{:<12} {:>10.2?}  ", algo.as_str(), elapsed);
+    match indices {
+      Some(indices) => {
+        print(&indices);
+        found = true;
+      }
+      None => println!("{}", none().red()),
+    }
+  }
+
+  if !searched {
+    return Err("No algorithm could search these numbers".to_string());
+  }
+  if !found {
+    return Err(none());
+  }
+  Ok(())
+}
+
+fn run_day04(matches: &ArgMatches) -> Result<(), String> {
+  let day = match matches.value_of("schema") {
+    Some(path) => DayFour::with_schema(load_schema(path)?),
+    None => DayFour::new(),
+  };
+  let path = Path::new(matches.value_of("input").unwrap());
+  let input = input::read(path, false)
+    .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+  let passports = day
+    .parse(&input)
+    .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+  let stdout = io::stdout();
+  let mut out = stdout.lock();
+  for export in day.export(&passports) {
+    let line = serde_json::to_string(&export).expect("exports serialize");
+    writeln!(out, "{}", line).map_err(|err| format!("Could not write passports: {}", err))?;
+  }
+  Ok(())
+}
+
+fn load_schema(path: &str) -> Result<Schema, String> {
+  Schema::load(Path::new(path)).map_err(|err| format!("Could not load schema {}: {}", path, err))
+}
+
+fn run_new(matches: &ArgMatches) -> Result<(), String> {
+  let day = match runner::parse_days(matches.value_of("DAY").unwrap())?[..] {
+    [day] => day,
+    _ => return Err("Only a single day can be created!".to_string()),
+  };
+  let created = scaffold::create(Path::new(""), day)
+    .map_err(|err| format!("Could not create day {:02}: {}", day, err))?;
+  for path in created {
+    println!("{} {}", "Wrote".green(), path.display());
+  }
+  Ok(())
+}
+
+fn run_validate(matches: &ArgMatches) -> Result<(), String> {
+  let day = match runner::parse_days(matches.value_of("DAY").unwrap())?[..] {
+    [day] => day,
+    _ => return Err("Only a single day can be validated!".to_string()),
+  };
+  let solver: Box<dyn problem::Solver> = match matches.value_of("schema") {
+    Some(path) if day == 4 => Box::new(DayFour::with_schema(load_schema(path)?)),
+    Some(_) => return Err("Only day 04 takes a schema!".to_string()),
+    None => registry::get(YEAR, day)
+      .ok_or_else(|| format!("Day {:02} is not implemented", day))?
+      .build(),
+  };
+  let path = matches
+    .value_of("input")
+    .map_or_else(|| answers::default_input(day), PathBuf::from);
+  let input = input::read(&path, matches.is_present("raw"))
+    .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+  let parsed = solver
+    .parse(&input)
+    .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+  let parts = match matches.value_of("part") {
+    Some(part) => vec![part.parse().unwrap()],
+    None => vec![1, 2],
+  };
+  let mut reports = Vec::new();
+  for part in parts {
+    let verdicts = match solver.validate(&parsed, part) {
+      Ok(verdicts) => verdicts,
+      Err(Error::Unimplemented) => {
+        return Err(format!("Day {:02} has no records to validate", day));
+      }
+      Err(err) => return Err(format!("{}: {}", path.display(), err)),
+    };
+    let input = path.display().to_string();
+    reports.push(validation::Report::new(day, part, &input, verdicts));
+  }
+
+  let out = &mut io::stdout();
+  match matches.value_of("format").unwrap() {
+    "json" => validation::write_json(&reports, out),
+    _ => validation::write_text(&reports, out),
+  }
+  .map_err(|err| format!("Could not write report: {}", err))
+}
+
+fn run_watch(matches: &ArgMatches) -> Result<(), String> {
+  let day = match runner::parse_days(matches.value_of("DAY").unwrap())?[..] {
+    [day] => day,
+    _ => return Err("Only a single day can be watched!".to_string()),
+  };
+  let interval: u64 = matches
+    .value_of("interval")
+    .unwrap()
+    .parse()
+    .map_err(|_| "Invalid interval!".to_string())?;
+  // Set when started through `cargo run`, so the same cargo rebuilds the day.
+  let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+  // The day is built into its own target directory: rebuilding the binary that runs `watch`
+  // fails on Windows, where a running executable cannot be replaced.
+  let target_dir = Path::new("target").join("watch");
+  let binary = target_dir
+    .join("debug")
+    .join(format!("aoc2020{}", env::consts::EXE_SUFFIX));
+
+  let mut last = None;
+  loop {
+    // Examples can come and go, so the files to watch are listed anew each time.
+    let snapshot = Snapshot::take(&watch::watched(day));
+    if last.as_ref() != Some(&snapshot) {
+      // Clear the screen and move the cursor to the top left.
+      print!("\x1b[2J\x1b[H");
+      println!("{}", format!("Watching day {:02} (Ctrl-C to stop)", day).dimmed());
+      io::stdout().flush().ok();
+
+      let built = Command::new(&cargo)
+        .args(["build", "--quiet", "--bin", "aoc2020", "--target-dir"])
+        .arg(&target_dir)
+        .status();
+      match built {
+        Ok(status) if status.success() => {
+          if let Err(err) = Command::new(&binary).arg(format!("{:02}", day)).status() {
+            println!("{}", format!("Could not run {}: {}", binary.display(), err).red());
+          }
+        }
+        // cargo has already printed why the build failed.
+        Ok(_) => {}
+        Err(err) => println!("{}", format!("Could not run {}: {}", cargo, err).red()),
+      }
+      last = Some(snapshot);
     }
+    thread::sleep(Duration::from_millis(interval));
   }
 }
@@ -0,0 +1,159 @@
+//! Helpers for the shapes puzzle inputs usually come in. Errors point at the offending line.
+
+use std::str::FromStr;
+
+use regex::{Captures, Regex};
+
+use crate::error::{Error, Result};
+
+/// A line of the input and its 1-based line number.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Line<'a> {
+  pub num: usize,
+  pub text: &'a str,
+}
+
+impl Line<'_> {
+  /// A parse error at the 1-based `column` of this line.
+  pub fn error(&self, column: usize, message: impl Into<String>) -> Error {
+    Error::parse(self.num, column, message)
+  }
+}
+
+/// The non-empty lines of `input`.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+  input
+    .split('\n')
+    .enumerate()
+    .filter(|(_, text)| !text.is_empty())
+    .map(|(idx, text)| Line { num: idx + 1, text })
+}
+
+/// Parses every non-empty line of `input` with `f`.
+pub fn map_lines<'a, T>(input: &'a str, f: impl FnMut(Line<'a>) -> Result<T>) -> Result<Vec<T>> {
+  lines(input).map(f).collect()
+}
+
+/// Parses every non-empty line of `input` as a number.
+pub fn numbers<T: FromStr>(input: &str) -> Result<Vec<T>> {
+  map_lines(input, |line| {
+    line
+      .text
+      .parse()
+      .map_err(|_| line.error(1, format!("invalid number {:?}", line.text)))
+  })
+}
+
+/// Parses `input` as a rectangular grid of characters, one row per non-empty line.
+pub fn grid(input: &str) -> Result<Vec<Vec<char>>> {
+  let mut grid: Vec<Vec<char>> = Vec::new();
+  for line in lines(input) {
+    let row: Vec<char> = line.text.chars().collect();
+    if let Some(first) = grid.first() {
+      if row.len() != first.len() {
+        let column = first.len().min(row.len()) + 1;
+        return Err(line.error(column, format!("expected {} columns", first.len())));
+      }
+    }
+    grid.push(row);
+  }
+
+  if grid.is_empty() {
+    return Err(Error::parse(1, 1, "empty grid"));
+  }
+  Ok(grid)
+}
+
+/// Splits `input` into groups of lines separated by blank lines. Groups are never empty.
+pub fn groups(input: &str) -> Vec<Vec<Line<'_>>> {
+  let mut groups = Vec::new();
+  let mut group = Vec::new();
+  for (idx, text) in input.split('\n').enumerate() {
+    if text.is_empty() {
+      if !group.is_empty() {
+        groups.push(group);
+        group = Vec::new();
+      }
+    } else {
+      group.push(Line { num: idx + 1, text });
+    }
+  }
+  if !group.is_empty() {
+    groups.push(group);
+  }
+  groups
+}
+
+/// Matches `pattern` against the whole of `line`, describing it as `what` if it does not match.
+pub fn captures<'a>(pattern: &Regex, line: Line<'a>, what: &str) -> Result<Captures<'a>> {
+  pattern
+    .captures(line.text)
+    .ok_or_else(|| line.error(1, format!("invalid {} {:?}", what, line.text)))
+}
+
+/// Parses the capture group `name` of `caps`, which matched `line`.
+pub fn capture<T: FromStr>(caps: &Captures, line: Line, name: &str) -> Result<T> {
+  let group = caps
+    .name(name)
+    .ok_or_else(|| line.error(1, format!("no {} in {:?}", name, line.text)))?;
+  group
+    .as_str()
+    .parse()
+    .map_err(|_| line.error(group.start() + 1, format!("invalid {} {:?}", name, group.as_str())))
+}
+
+#[cfg(test)]
+mod tests {
+  use regex::Regex;
+
+  use crate::error::Error;
+  use crate::parse::{self, Line};
+
+  #[test]
+  fn numbers() {
+    assert_eq!(parse::numbers::<i32>("1\n-2\n\n3\n"), Ok(vec![1, -2, 3]));
+    assert_eq!(
+      parse::numbers::<u8>("1\n\n300\n"),
+      Err(Error::parse(3, 1, "invalid number \"300\""))
+    );
+  }
+
+  #[test]
+  fn grid() {
+    assert_eq!(parse::grid(".#\n#.\n"), Ok(vec![vec!['.', '#'], vec!['#', '.']]));
+    assert_eq!(parse::grid(".#\n#\n"), Err(Error::parse(2, 2, "expected 2 columns")));
+    assert_eq!(parse::grid("\n"), Err(Error::parse(1, 1, "empty grid")));
+  }
+
+  #[test]
+  fn groups() {
+    let groups = parse::groups("a\nb\n\n\nc\n");
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0][1], Line { num: 2, text: "b" });
+    assert_eq!(groups[1], vec![Line { num: 5, text: "c" }]);
+    assert!(parse::groups("\n\n").is_empty());
+  }
+
+  #[test]
+  fn captures() {
+    let pattern = Regex::new(r"^(?P<name>\w+)=(?P<value>\S+)$").unwrap();
+    let line = Line { num: 4, text: "x=12" };
+    let caps = parse::captures(&pattern, line, "setting").unwrap();
+    assert_eq!(parse::capture::<u8>(&caps, line, "value"), Ok(12));
+    assert_eq!(
+      parse::capture::<u8>(&caps, line, "name"),
+      Err(Error::parse(4, 1, "invalid name \"x\""))
+    );
+
+    let line = Line { num: 5, text: "x=999" };
+    let caps = parse::captures(&pattern, line, "setting").unwrap();
+    assert_eq!(
+      parse::capture::<u8>(&caps, line, "value"),
+      Err(Error::parse(5, 3, "invalid value \"999\""))
+    );
+    assert_eq!(
+      parse::captures(&pattern, Line { num: 6, text: "x" }, "setting").err(),
+      Some(Error::parse(6, 1, "invalid setting \"x\""))
+    );
+  }
+}
@@ -1,28 +1,76 @@
-pub trait Problem {
+use std::any::Any;
+
+use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::validation::Verdict;
+
+/// Problems and their inputs are shared between threads, so that days and parts can be solved in
+/// parallel.
+pub trait Problem: Send + Sync {
+  /// The puzzle input, parsed once and shared by both parts.
+  type Input: Send + Sync + 'static;
+
   fn new() -> Self
   where
     Self: Sized;
 
-  fn debug() -> Self
-  where
-    Self: Sized,
-  {
-    Self::new()
+  fn parse(&self, input: &str) -> Result<Self::Input>;
+
+  /// `log` carries diagnostics to stderr at the level chosen for this day on the command line.
+  fn part_one(&self, _input: &Self::Input, _log: &Log) -> Result<String> {
+    Err(Error::Unimplemented)
+  }
+
+  fn part_two(&self, _input: &Self::Input, _log: &Log) -> Result<String> {
+    Err(Error::Unimplemented)
+  }
+
+  /// A verdict per record of `input` under the rules of `part`, for days whose answer is a count
+  /// of valid records.
+  fn validate(&self, _input: &Self::Input, _part: u8) -> Result<Vec<Verdict>> {
+    Err(Error::Unimplemented)
   }
+}
+
+/// A parsed input whose type is only known to the `Problem` that produced it.
+pub struct Parsed(Box<dyn Any + Send + Sync>);
+
+/// Object-safe view of a `Problem`, so problems with different `Input` types can be stored and
+/// run side by side.
+pub trait Solver: Send + Sync {
+  fn parse(&self, input: &str) -> Result<Parsed>;
+
+  /// Panics if `input` was not produced by this solver's `parse`.
+  fn part_one(&self, input: &Parsed, log: &Log) -> Result<String>;
+
+  /// Panics if `input` was not produced by this solver's `parse`.
+  fn part_two(&self, input: &Parsed, log: &Log) -> Result<String>;
 
-  fn soln_one(&self) -> Option<String> {
-    None
+  /// Panics if `input` was not produced by this solver's `parse`.
+  fn validate(&self, input: &Parsed, part: u8) -> Result<Vec<Verdict>>;
+}
+
+impl<P: Problem> Solver for P {
+  fn parse(&self, input: &str) -> Result<Parsed> {
+    Problem::parse(self, input).map(|parsed| Parsed(Box::new(parsed)))
   }
 
-  fn part_one(&self, _input: &str) -> Option<String> {
-    None
+  fn part_one(&self, input: &Parsed, log: &Log) -> Result<String> {
+    Problem::part_one(self, downcast::<P>(input), log)
   }
 
-  fn soln_two(&self) -> Option<String> {
-    None
+  fn part_two(&self, input: &Parsed, log: &Log) -> Result<String> {
+    Problem::part_two(self, downcast::<P>(input), log)
   }
 
-  fn part_two(&self, _input: &str) -> Option<String> {
-    None
+  fn validate(&self, input: &Parsed, part: u8) -> Result<Vec<Verdict>> {
+    Problem::validate(self, downcast::<P>(input), part)
   }
 }
+
+fn downcast<P: Problem>(input: &Parsed) -> &P::Input {
+  input
+    .0
+    .downcast_ref()
+    .expect("input was parsed by a different problem")
+}
@@ -0,0 +1,86 @@
+use crate::problem::Solver;
+
+/// A `Problem` implementation registered for a given (year, day).
+///
+/// Each day module registers itself with the `register!` macro, so the runner never has to
+/// know about individual days.
+pub struct Registration {
+  pub year: u16,
+  pub day: u8,
+  new: fn() -> Box<dyn Solver>,
+}
+
+impl Registration {
+  #[doc(hidden)]
+  pub const fn new(year: u16, day: u8, new: fn() -> Box<dyn Solver>) -> Self {
+    Self { year, day, new }
+  }
+
+  /// Builds a fresh instance of the registered problem.
+  pub fn build(&self) -> Box<dyn Solver> {
+    (self.new)()
+  }
+}
+
+inventory::collect!(Registration);
+
+/// Registers a `Problem` implementation for the given year and day, e.g.
+/// `crate::register!(2020, 1, DayOne);` next to its `impl Problem` block.
+#[macro_export]
+macro_rules! register {
+  ($year:expr, $day:expr, $problem:ty) => {
+    $crate::registry::inventory::submit! {
+      $crate::registry::Registration::new(
+        $year,
+        $day,
+        || Box::new(<$problem as $crate::problem::Problem>::new()),
+      )
+    }
+  };
+}
+
+#[doc(hidden)]
+pub use inventory;
+
+/// Returns every registered problem, sorted by (year, day).
+pub fn all() -> Vec<&'static Registration> {
+  let mut registrations: Vec<&Registration> = inventory::iter::<Registration>.into_iter().collect();
+  registrations.sort_by_key(|reg| (reg.year, reg.day));
+  registrations
+}
+
+/// Returns every registered problem for `year`, sorted by day.
+pub fn for_year(year: u16) -> Vec<&'static Registration> {
+  all().into_iter().filter(|reg| reg.year == year).collect()
+}
+
+/// Returns the problem registered for `year` and `day`, if any.
+pub fn get(year: u16, day: u8) -> Option<&'static Registration> {
+  inventory::iter::<Registration>
+    .into_iter()
+    .find(|reg| reg.year == year && reg.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::registry;
+  use crate::YEAR;
+
+  #[test]
+  fn days_registered_once() {
+    let all: Vec<(u16, u8)> = registry::all().iter().map(|reg| (reg.year, reg.day)).collect();
+    let mut distinct = all.clone();
+    distinct.dedup();
+    assert_eq!(all, distinct);
+    assert!(all.iter().all(|&(_, day)| (1..=25).contains(&day)));
+  }
+
+  #[test]
+  fn get_builds() {
+    assert!(registry::get(YEAR, 1).is_some());
+    assert!(registry::get(YEAR, 0).is_none());
+    for reg in registry::all() {
+      reg.build();
+    }
+  }
+}
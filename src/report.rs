@@ -0,0 +1,349 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+
+/// How the answer to a part compares to the expected one.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+  Pass,
+  Fail,
+  /// There is an answer, but no expected answer to check it against.
+  Unknown,
+  Unimplemented,
+  Error,
+}
+
+impl Status {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Status::Pass => "pass",
+      Status::Fail => "fail",
+      Status::Unknown => "unknown",
+      Status::Unimplemented => "unimplemented",
+      Status::Error => "error",
+    }
+  }
+}
+
+/// The result of a single (day, part).
+#[derive(Clone, Debug, Serialize)]
+pub struct Record {
+  pub year: u16,
+  pub day: u8,
+  /// Path of the input file the part was solved for.
+  pub input: String,
+  pub part: u8,
+  pub expected: Option<String>,
+  pub actual: Option<String>,
+  pub status: Status,
+  pub error: Option<String>,
+  pub duration_ns: u64,
+}
+
+impl Record {
+  pub fn new(
+    year: u16,
+    day: u8,
+    input: &str,
+    part: u8,
+    expected: Option<String>,
+    actual: &Result<String>,
+    duration: Duration,
+  ) -> Self {
+    let status = match (actual, &expected) {
+      (Ok(_), None) => Status::Unknown,
+      (Ok(answer), Some(expected)) if answer == expected => Status::Pass,
+      (Ok(_), Some(_)) => Status::Fail,
+      (Err(Error::Unimplemented), _) => Status::Unimplemented,
+      (Err(_), _) => Status::Error,
+    };
+
+    Self {
+      year,
+      day,
+      input: input.to_string(),
+      part,
+      expected,
+      actual: actual.as_ref().ok().cloned(),
+      status,
+      error: actual.as_ref().err().map(Error::to_string),
+      duration_ns: duration.as_nanos() as u64,
+    }
+  }
+}
+
+/// How many parts ended up in each status.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Summary {
+  pub pass: usize,
+  pub fail: usize,
+  pub unknown: usize,
+  pub unimplemented: usize,
+  pub error: usize,
+}
+
+impl Summary {
+  pub fn of(records: &[Record]) -> Self {
+    let mut summary = Self::default();
+    for record in records {
+      match record.status {
+        Status::Pass => summary.pass += 1,
+        Status::Fail => summary.fail += 1,
+        Status::Unknown => summary.unknown += 1,
+        Status::Unimplemented => summary.unimplemented += 1,
+        Status::Error => summary.error += 1,
+      }
+    }
+    summary
+  }
+
+  /// Whether no part had a wrong answer or an error.
+  pub fn is_success(&self) -> bool {
+    self.fail == 0 && self.error == 0
+  }
+}
+
+/// Output formats of the runner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+  /// Colored, human-readable text.
+  Text,
+  Json,
+  Tsv,
+  Junit,
+}
+
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, String> {
+    match s {
+      "text" => Ok(Format::Text),
+      "json" => Ok(Format::Json),
+      "tsv" => Ok(Format::Tsv),
+      "junit" => Ok(Format::Junit),
+      _ => Err(format!("Unknown format {:?}", s)),
+    }
+  }
+}
+
+/// Writes `records` in `format`. `Format::Text` is printed by the runner as it goes, so it is
+/// not handled here.
+pub fn write(format: Format, records: &[Record], out: &mut impl Write) -> io::Result<()> {
+  match format {
+    Format::Text => Ok(()),
+    Format::Json => write_json(records, out),
+    Format::Tsv => write_tsv(records, out),
+    Format::Junit => write_junit(records, out),
+  }
+}
+
+fn write_json(records: &[Record], out: &mut impl Write) -> io::Result<()> {
+  serde_json::to_writer_pretty(&mut *out, records)?;
+  writeln!(out)
+}
+
+fn write_tsv(records: &[Record], out: &mut impl Write) -> io::Result<()> {
+  // Tabs and newlines would break the columns.
+  fn field(value: Option<&str>) -> String {
+    value.unwrap_or("").replace(&['\t', '\n'][..], " ")
+  }
+
+  writeln!(out, "year\tday\tinput\tpart\texpected\tactual\tstatus\tduration_ns")?;
+  for record in records {
+    writeln!(
+      out,
+      "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+      record.year,
+      record.day,
+      field(Some(&record.input)),
+      record.part,
+      field(record.expected.as_deref()),
+      field(record.actual.as_deref().or(record.error.as_deref())),
+      record.status.as_str(),
+      record.duration_ns
+    )?;
+  }
+  Ok(())
+}
+
+fn write_junit(records: &[Record], out: &mut impl Write) -> io::Result<()> {
+  fn count(records: &[&Record], status: Status) -> usize {
+    records.iter().filter(|record| record.status == status).count()
+  }
+  fn seconds(records: &[&Record]) -> f64 {
+    records.iter().map(|record| record.duration_ns).sum::<u64>() as f64 / 1e9
+  }
+
+  let all: Vec<&Record> = records.iter().collect();
+  writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+  writeln!(
+    out,
+    r#"<testsuites name="aoc" tests="{}" failures="{}" errors="{}" skipped="{}" time="{:.6}">"#,
+    all.len(),
+    count(&all, Status::Fail),
+    count(&all, Status::Error),
+    count(&all, Status::Unimplemented),
+    seconds(&all)
+  )?;
+
+  let mut days: Vec<(u16, u8)> = records.iter().map(|r| (r.year, r.day)).collect();
+  days.dedup();
+  for (year, day) in days {
+    let suite: Vec<&Record> = all
+      .iter()
+      .copied()
+      .filter(|r| r.year == year && r.day == day)
+      .collect();
+    writeln!(
+      out,
+      r#"  <testsuite name="{}.day{:02}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{:.6}">"#,
+      year,
+      day,
+      suite.len(),
+      count(&suite, Status::Fail),
+      count(&suite, Status::Error),
+      count(&suite, Status::Unimplemented),
+      seconds(&suite)
+    )?;
+    for record in suite {
+      write!(
+        out,
+        r#"    <testcase classname="{}.day{:02}" name="{} part{}" time="{:.6}""#,
+        year,
+        day,
+        escape(&record.input),
+        record.part,
+        record.duration_ns as f64 / 1e9
+      )?;
+      let expected = record.expected.as_deref().unwrap_or("");
+      let actual = record.actual.as_deref().unwrap_or("");
+      let error = record.error.as_deref().unwrap_or("");
+      match record.status {
+        Status::Pass => writeln!(out, "/>")?,
+        Status::Unknown => {
+          writeln!(out, ">")?;
+          writeln!(out, "      <system-out>{}</system-out>", escape(actual))?;
+          writeln!(out, "    </testcase>")?;
+        }
+        Status::Fail => {
+          writeln!(out, ">")?;
+          let message = format!("expected {}, got {}", expected, actual);
+          writeln!(out, r#"      <failure message="{}"/>"#, escape(&message))?;
+          writeln!(out, "    </testcase>")?;
+        }
+        Status::Unimplemented => {
+          writeln!(out, ">")?;
+          writeln!(out, r#"      <skipped message="{}"/>"#, escape(error))?;
+          writeln!(out, "    </testcase>")?;
+        }
+        Status::Error => {
+          writeln!(out, ">")?;
+          writeln!(out, r#"      <error message="{}"/>"#, escape(error))?;
+          writeln!(out, "    </testcase>")?;
+        }
+      }
+    }
+    writeln!(out, "  </testsuite>")?;
+  }
+
+  writeln!(out, "</testsuites>")
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use crate::error::Error;
+  use crate::report::{self, Format, Record, Status, Summary};
+
+  fn records() -> Vec<Record> {
+    let expected = Some("42".to_string());
+    let ms = Duration::from_millis(1);
+    vec![
+      Record::new(2020, 1, "a.txt", 1, expected.clone(), &Ok("42".to_string()), ms),
+      Record::new(2020, 1, "a.txt", 2, expected, &Ok("4\t3".to_string()), ms),
+      Record::new(2020, 2, "b.txt", 1, None, &Ok("7".to_string()), ms),
+      Record::new(2020, 2, "b.txt", 2, None, &Err(Error::Unimplemented), ms),
+      Record::new(2020, 3, "c.txt", 1, None, &Err(Error::parse(1, 2, "bad <input>")), ms),
+    ]
+  }
+
+  fn render(format: Format) -> String {
+    let mut out = Vec::new();
+    report::write(format, &records(), &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+  }
+
+  #[test]
+  fn status() {
+    let statuses: Vec<Status> = records().iter().map(|record| record.status).collect();
+    assert_eq!(
+      statuses,
+      vec![
+        Status::Pass,
+        Status::Fail,
+        Status::Unknown,
+        Status::Unimplemented,
+        Status::Error
+      ]
+    );
+  }
+
+  #[test]
+  fn summary() {
+    let summary = Summary::of(&records());
+    assert_eq!(
+      summary,
+      Summary {
+        pass: 1,
+        fail: 1,
+        unknown: 1,
+        unimplemented: 1,
+        error: 1,
+      }
+    );
+    assert!(!summary.is_success());
+    assert!(Summary::of(&records()[2..4]).is_success());
+  }
+
+  #[test]
+  fn tsv() {
+    let tsv = render(Format::Tsv);
+    let lines: Vec<&str> = tsv.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[2], "2020\t1\ta.txt\t2\t42\t4 3\tfail\t1000000");
+    assert_eq!(lines[4], "2020\t2\tb.txt\t2\t\tunimplemented\tunimplemented\t1000000");
+  }
+
+  #[test]
+  fn json() {
+    let json: serde_json::Value = serde_json::from_str(&render(Format::Json)).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 5);
+    assert_eq!(json[0]["status"], "pass");
+    assert_eq!(json[4]["error"], "parse error at 1:2: bad <input>");
+  }
+
+  #[test]
+  fn junit() {
+    let xml = render(Format::Junit);
+    assert!(xml.contains(r#"<testsuites name="aoc" tests="5" failures="1" errors="1" skipped="1""#));
+    assert!(xml.contains(r#"<testcase classname="2020.day01" name="a.txt part1" time="0.001000"/>"#));
+    assert!(xml.contains(r#"<failure message="expected 42, got 4	3"/>"#));
+    assert!(xml.contains(r#"<error message="parse error at 1:2: bad &lt;input&gt;"/>"#));
+  }
+}
@@ -0,0 +1,256 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::problem::{Parsed, Solver};
+
+/// Wall time spent in each stage of solving a day.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timings {
+  pub read: Duration,
+  pub parse: Duration,
+  pub part_one: Duration,
+  pub part_two: Duration,
+}
+
+/// The answers to the parts of a day that were run, and how long they took.
+pub struct Outcome {
+  pub part_one: Option<Result<String>>,
+  pub part_two: Option<Result<String>>,
+  pub timings: Timings,
+}
+
+impl Outcome {
+  fn solved(
+    part_one: Option<(Result<String>, Duration)>,
+    part_two: Option<(Result<String>, Duration)>,
+  ) -> Self {
+    let mut timings = Timings::default();
+    let part_one = part_one.map(|(answer, duration)| {
+      timings.part_one = duration;
+      answer
+    });
+    let part_two = part_two.map(|(answer, duration)| {
+      timings.part_two = duration;
+      answer
+    });
+    Self {
+      part_one,
+      part_two,
+      timings,
+    }
+  }
+
+  fn parsed_in(mut self, duration: Duration) -> Self {
+    self.timings.parse = duration;
+    self
+  }
+
+  /// The selected parts failed with `err` before they could run.
+  pub fn failed(err: Error, part: Option<u8>) -> Self {
+    Self {
+      part_one: runs(part, 1).then(|| Err(err.clone())),
+      part_two: runs(part, 2).then_some(Err(err)),
+      timings: Timings::default(),
+    }
+  }
+}
+
+/// Whether `num` is run when only `part` is selected, or all parts if it is None.
+fn runs(part: Option<u8>, num: u8) -> bool {
+  part.is_none_or(|part| part == num)
+}
+
+/// Runs `f` and returns its result along with how long it took.
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+  let start = Instant::now();
+  let result = f();
+  (result, start.elapsed())
+}
+
+/// Parses `input` once and solves `part` with it, or both parts if it is None. A parse error is
+/// reported for every selected part.
+pub fn solve(solver: &dyn Solver, input: &str, part: Option<u8>, log: &Log) -> Outcome {
+  let (parsed, parse_time) = time(|| solver.parse(input));
+  let parsed = match parsed {
+    Ok(parsed) => parsed,
+    Err(err) => return Outcome::failed(err, part).parsed_in(parse_time),
+  };
+
+  let part_one = runs(part, 1).then(|| time(|| solve_part(solver, &parsed, 1, log)));
+  let part_two = runs(part, 2).then(|| time(|| solve_part(solver, &parsed, 2, log)));
+  Outcome::solved(part_one, part_two).parsed_in(parse_time)
+}
+
+/// An input to solve with `solver`, logging to `log`.
+pub struct Task<'a> {
+  pub solver: &'a dyn Solver,
+  pub input: &'a str,
+  pub log: Log,
+}
+
+/// Like `solve` for every task, on up to `jobs` threads. All inputs are parsed first, then every
+/// selected part of every input is solved, so no more than `jobs` parses or parts run at once.
+/// The outcomes are in the order of `tasks`.
+pub fn solve_parallel(tasks: &[Task], part: Option<u8>, jobs: usize) -> Vec<Outcome> {
+  let parsed = map_parallel(tasks, jobs, |task| time(|| task.solver.parse(task.input)));
+
+  let mut parts = Vec::new();
+  for (idx, (parsed, _)) in parsed.iter().enumerate() {
+    if let Ok(parsed) = parsed {
+      parts.extend([1, 2].iter().filter(|&&num| runs(part, num)).map(|&num| (idx, parsed, num)));
+    }
+  }
+  let mut answers = map_parallel(&parts, jobs, |&(idx, parsed, num)| {
+    let task = &tasks[idx];
+    time(|| solve_part(task.solver, parsed, num, &task.log))
+  })
+  .into_iter();
+
+  parsed
+    .iter()
+    .map(|(parsed, parse_time)| match parsed {
+      Ok(_) => {
+        let mut next = |num| runs(part, num).then(|| answers.next().expect("every part is solved"));
+        let part_one = next(1);
+        let part_two = next(2);
+        Outcome::solved(part_one, part_two).parsed_in(*parse_time)
+      }
+      Err(err) => Outcome::failed(err.clone(), part).parsed_in(*parse_time),
+    })
+    .collect()
+}
+
+fn solve_part(solver: &dyn Solver, parsed: &Parsed, num: u8, log: &Log) -> Result<String> {
+  match num {
+    1 => solver.part_one(parsed, log),
+    _ => solver.part_two(parsed, log),
+  }
+}
+
+/// Calls `f` on every item using up to `jobs` threads, and returns the results in the order of
+/// `items`.
+pub fn map_parallel<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+  T: Sync,
+  R: Send,
+  F: Fn(&T) -> R + Sync,
+{
+  let next = AtomicUsize::new(0);
+  let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+  thread::scope(|scope| {
+    for _ in 0..jobs.clamp(1, items.len().max(1)) {
+      scope.spawn(|| loop {
+        let idx = next.fetch_add(1, Ordering::Relaxed);
+        let Some(item) = items.get(idx) else { break };
+        let result = f(item);
+        results.lock().unwrap()[idx] = Some(result);
+      });
+    }
+  });
+
+  results
+    .into_inner()
+    .unwrap()
+    .into_iter()
+    .map(|result| result.expect("every item is solved"))
+    .collect()
+}
+
+/// Parses a selection of days such as `7`, `3-7`, `1,4,9` or `1-3,9`, in the order given.
+pub fn parse_days(spec: &str) -> std::result::Result<Vec<u8>, String> {
+  let day = |s: &str| match s.trim().parse::<u8>() {
+    Ok(day) if (1..=25).contains(&day) => Ok(day),
+    _ => Err(format!("Invalid day {:?}", s)),
+  };
+
+  let mut days = Vec::new();
+  for part in spec.split(',') {
+    match part.find('-') {
+      Some(dash) => {
+        let (start, end) = (day(&part[..dash])?, day(&part[dash + 1..])?);
+        if start > end {
+          return Err(format!("Invalid day range {:?}", part));
+        }
+        days.extend(start..=end);
+      }
+      None => days.push(day(part)?),
+    }
+  }
+  Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::day01::DayOne;
+  use crate::log::Log;
+  use crate::problem::Problem;
+  use crate::runner::{map_parallel, parse_days, solve, solve_parallel, Task};
+
+  #[test]
+  fn solve_one_part() {
+    let input = "1721\n979\n366\n299\n675\n1456\n";
+    let outcome = solve(&DayOne::new(), input, Some(2), &Log::off());
+    assert!(outcome.part_one.is_none());
+    assert_eq!(outcome.part_two, Some(Ok("241861950".to_string())));
+
+    let outcome = solve(&DayOne::new(), input, None, &Log::off());
+    assert_eq!(outcome.part_one, Some(Ok("514579".to_string())));
+    assert_eq!(outcome.part_two, Some(Ok("241861950".to_string())));
+
+    let outcome = solve(&DayOne::new(), "x", None, &Log::off());
+    assert!(outcome.part_one.unwrap().is_err());
+    assert!(outcome.part_two.unwrap().is_err());
+  }
+
+  #[test]
+  fn parallel_tasks() {
+    let day = DayOne::new();
+    let task = |input| Task {
+      solver: &day,
+      input,
+      log: Log::off(),
+    };
+    let tasks = [task("1721\n979\n366\n299\n675\n1456\n"), task("x"), task("1010\n1010\n")];
+    let outcomes = solve_parallel(&tasks, None, 3);
+    assert_eq!(outcomes[0].part_one, Some(Ok("514579".to_string())));
+    assert_eq!(outcomes[0].part_two, Some(Ok("241861950".to_string())));
+    assert!(outcomes[1].part_one.as_ref().unwrap().is_err());
+    assert_eq!(outcomes[2].part_one, Some(Ok("1020100".to_string())));
+
+    let outcomes = solve_parallel(&tasks, Some(2), 2);
+    assert!(outcomes[0].part_one.is_none());
+    assert_eq!(outcomes[0].part_two, Some(Ok("241861950".to_string())));
+  }
+
+  #[test]
+  fn parallel_in_order() {
+    let items: Vec<u64> = (0..50).collect();
+    for jobs in [1, 4, 100] {
+      let squares = map_parallel(&items, jobs, |&n| n * n);
+      assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<_>>());
+    }
+    assert!(map_parallel(&[] as &[u8], 4, |&n| n).is_empty());
+  }
+
+  #[test]
+  fn days() {
+    assert_eq!(parse_days("02"), Ok(vec![2]));
+    assert_eq!(parse_days("3-7"), Ok(vec![3, 4, 5, 6, 7]));
+    assert_eq!(parse_days("1,4,9"), Ok(vec![1, 4, 9]));
+    assert_eq!(parse_days("1-3, 25"), Ok(vec![1, 2, 3, 25]));
+  }
+
+  #[test]
+  fn invalid_days() {
+    assert!(parse_days("0").is_err());
+    assert!(parse_days("26").is_err());
+    assert!(parse_days("7-3").is_err());
+    assert!(parse_days("1,,2").is_err());
+    assert!(parse_days("one").is_err());
+  }
+}
@@ -0,0 +1,210 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const NAMES: [&str; 25] = [
+  "One",
+  "Two",
+  "Three",
+  "Four",
+  "Five",
+  "Six",
+  "Seven",
+  "Eight",
+  "Nine",
+  "Ten",
+  "Eleven",
+  "Twelve",
+  "Thirteen",
+  "Fourteen",
+  "Fifteen",
+  "Sixteen",
+  "Seventeen",
+  "Eighteen",
+  "Nineteen",
+  "Twenty",
+  "TwentyOne",
+  "TwentyTwo",
+  "TwentyThree",
+  "TwentyFour",
+  "TwentyFive",
+];
+
+/// `src/dayNN.rs` for a new day. `{{D}}` and `{{NAME}}` are filled in by `module`. Its examples
+/// are tested from `examples/dayNN/answers.toml`, so it needs no tests of its own.
+const TEMPLATE: &str = r#"use crate::error::{Error, Result};
+use crate::log::Log;
+use crate::parse;
+use crate::problem::Problem;
+
+#[derive(Default)]
+pub struct {{NAME}} {}
+
+crate::register!(2020, {{D}}, {{NAME}});
+
+impl Problem for {{NAME}} {
+  type Input = Vec<String>;
+
+  fn new() -> Self {
+    Self {}
+  }
+
+  fn parse(&self, input: &str) -> Result<Vec<String>> {
+    Ok(parse::lines(input).map(|line| line.text.to_string()).collect())
+  }
+
+  fn part_one(&self, _lines: &Vec<String>, _log: &Log) -> Result<String> {
+    Err(Error::Unimplemented)
+  }
+
+  fn part_two(&self, _lines: &Vec<String>, _log: &Log) -> Result<String> {
+    Err(Error::Unimplemented)
+  }
+}
+"#;
+
+/// `examples/dayNN/answers.toml`, listing the first example with its answers left to fill in.
+pub fn example_answers(day: u8) -> String {
+  format!(
+    "[[input]]\nfile = \"examples/day{:02}/example1.txt\"\n# part1 = \"\"\n# part2 = \"\"\n",
+    day
+  )
+}
+
+/// The name of the `Problem` type for `day`, e.g. `DayTen`.
+pub fn type_name(day: u8) -> String {
+  format!("Day{}", NAMES[day as usize - 1])
+}
+
+/// The source of a new, unsolved `src/dayNN.rs`.
+pub fn module(day: u8) -> String {
+  TEMPLATE
+    .replace("{{D}}", &day.to_string())
+    .replace("{{NAME}}", &type_name(day))
+}
+
+/// Declares `dayNN` in the contents of `lib.rs`, by uncommenting its placeholder line or adding
+/// it in alphabetical order. Returns None if it is already declared.
+pub fn register(lib: &str, day: u8) -> Option<String> {
+  let declaration = format!("pub mod day{:02};", day);
+  if lib.lines().any(|line| line == declaration) {
+    return None;
+  }
+
+  let placeholder = format!("// {}", declaration);
+  let mut lines: Vec<&str> = lib.lines().collect();
+  match lines.iter().position(|line| *line == placeholder) {
+    Some(idx) => lines[idx] = &declaration,
+    None => {
+      // Modules are declared in alphabetical order.
+      let idx = lines
+        .iter()
+        .position(|line| line.trim_start_matches("// ") > declaration.as_str())
+        .unwrap_or(lines.len());
+      lines.insert(idx, &declaration);
+    }
+  }
+
+  let mut lib = lines.join("\n");
+  lib.push('\n');
+  Some(lib)
+}
+
+/// Creates everything needed to start on `day` inside `root`: its module, declared in
+/// `src/lib.rs`, an empty input and an empty first example listed in the example answers.
+/// Refuses to touch a day whose module already exists. Returns the files that were created or
+/// changed.
+pub fn create(root: &Path, day: u8) -> io::Result<Vec<PathBuf>> {
+  let source = root.join(format!("src/day{:02}.rs", day));
+  let lib_path = root.join("src/lib.rs");
+  let input = root.join(format!("inputs/day{:02}.txt", day));
+  let example = root.join(format!("examples/day{:02}/example1.txt", day));
+  let answers = root.join(format!("examples/day{:02}/answers.toml", day));
+
+  if source.exists() {
+    return Err(io::Error::new(
+      io::ErrorKind::AlreadyExists,
+      format!("{} already exists", source.display()),
+    ));
+  }
+  let lib = fs::read_to_string(&lib_path)?;
+
+  let mut created = Vec::new();
+  fs::write(&source, module(day))?;
+  created.push(source);
+  if let Some(lib) = register(&lib, day) {
+    fs::write(&lib_path, lib)?;
+    created.push(lib_path);
+  }
+  // Existing inputs and examples are kept, they may well have been downloaded first.
+  for (file, contents) in [
+    (input, String::new()),
+    (example, String::new()),
+    (answers, example_answers(day)),
+  ] {
+    if !file.exists() {
+      if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+      }
+      fs::write(&file, contents)?;
+      created.push(file);
+    }
+  }
+  Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use crate::answers::Answers;
+  use crate::scaffold;
+
+  #[test]
+  fn module() {
+    let module = scaffold::module(10);
+    assert!(module.contains("pub struct DayTen {}"));
+    assert!(module.contains("crate::register!(2020, 10, DayTen);"));
+    assert!(!module.contains("{{"));
+    assert_eq!(scaffold::type_name(25), "DayTwentyFive");
+  }
+
+  #[test]
+  fn register() {
+    let lib = "pub mod day01;\n// pub mod day02;\npub mod error;\n";
+    assert_eq!(
+      scaffold::register(lib, 2).unwrap(),
+      "pub mod day01;\npub mod day02;\npub mod error;\n"
+    );
+    assert_eq!(scaffold::register("pub mod day02;\n", 2), None);
+
+    let lib = "pub mod answers;\npub mod day01;\npub mod day03;\npub mod error;\n";
+    assert_eq!(
+      scaffold::register(lib, 2).unwrap(),
+      "pub mod answers;\npub mod day01;\npub mod day02;\npub mod day03;\npub mod error;\n"
+    );
+  }
+
+  #[test]
+  fn create() {
+    let root = std::env::temp_dir().join(format!("aoc2020-scaffold-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/lib.rs"), "pub mod day09;\n// pub mod day10;\n").unwrap();
+
+    let created = scaffold::create(&root, 10).unwrap();
+    assert_eq!(created.len(), 5);
+    assert_eq!(
+      fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+      "pub mod day09;\npub mod day10;\n"
+    );
+    assert_eq!(fs::read_to_string(root.join("inputs/day10.txt")).unwrap(), "");
+    assert!(root.join("examples/day10/example1.txt").exists());
+    let answers = fs::read_to_string(root.join("examples/day10/answers.toml")).unwrap();
+    assert_eq!(Answers::parse(&answers).unwrap().inputs[0].part1, None);
+
+    // Never overwrites a day.
+    assert!(scaffold::create(&root, 10).is_err());
+    fs::remove_dir_all(&root).unwrap();
+  }
+}
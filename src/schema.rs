@@ -0,0 +1,276 @@
+//! Declarative rules for documents made of `key:value` fields, such as the passports of day 4,
+//! loaded from TOML files like `schemas/passport.toml`.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::validation::Check;
+
+const PASSPORT: &str = include_str!("../schemas/passport.toml");
+
+/// How the value of a field is checked.
+#[derive(Clone, Debug)]
+pub enum Rule {
+  /// Any value.
+  Any,
+  /// An integer in the range.
+  Range(RangeInclusive<i64>),
+  /// A match of the pattern, described by the string.
+  Pattern(Regex, String),
+  /// One of the listed values.
+  OneOf(Vec<String>),
+  /// An integer immediately followed by one of the units, in the range of that unit.
+  Units(BTreeMap<String, RangeInclusive<i64>>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Field {
+  pub key: String,
+  pub required: bool,
+  pub rule: Rule,
+}
+
+/// The fields a document may have and what their values must look like.
+#[derive(Clone, Debug)]
+pub struct Schema {
+  fields: Vec<Field>,
+}
+
+/// A field as written in a schema file, with at most one of the rules set.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldSpec {
+  key: String,
+  #[serde(default = "FieldSpec::required")]
+  required: bool,
+  range: Option<[i64; 2]>,
+  pattern: Option<String>,
+  expected: Option<String>,
+  one_of: Option<Vec<String>>,
+  units: Option<BTreeMap<String, [i64; 2]>>,
+}
+
+impl FieldSpec {
+  fn required() -> bool {
+    true
+  }
+
+  fn into_field(self) -> Result<Field, String> {
+    let FieldSpec {
+      key,
+      required,
+      range,
+      pattern,
+      expected,
+      one_of,
+      units,
+    } = self;
+    let rules = [range.is_some(), pattern.is_some(), one_of.is_some(), units.is_some()];
+    if rules.iter().filter(|&&set| set).count() > 1 {
+      return Err(format!("field {} has more than one rule", key));
+    }
+    if expected.is_some() && pattern.is_none() {
+      return Err(format!("field {} describes a pattern it does not have", key));
+    }
+
+    let rule = if let Some([min, max]) = range {
+      Rule::Range(min..=max)
+    } else if let Some(pattern) = pattern {
+      let regex = Regex::new(&pattern)
+        .map_err(|err| format!("field {} has an invalid pattern: {}", key, err))?;
+      let expected = expected.unwrap_or_else(|| format!("a match of {}", pattern));
+      Rule::Pattern(regex, expected)
+    } else if let Some(values) = one_of {
+      Rule::OneOf(values)
+    } else if let Some(units) = units {
+      let units = units.into_iter().map(|(unit, [min, max])| (unit, min..=max));
+      Rule::Units(units.collect())
+    } else {
+      Rule::Any
+    };
+    Ok(Field {
+      key,
+      required,
+      rule,
+    })
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaSpec {
+  #[serde(default, rename = "field")]
+  fields: Vec<FieldSpec>,
+}
+
+impl Schema {
+  pub fn parse(text: &str) -> io::Result<Self> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let spec: SchemaSpec = toml::from_str(text).map_err(|err| invalid(err.to_string()))?;
+    let mut fields: Vec<Field> = Vec::new();
+    for spec in spec.fields {
+      if fields.iter().any(|field| field.key == spec.key) {
+        return Err(invalid(format!("field {} is defined twice", spec.key)));
+      }
+      fields.push(spec.into_field().map_err(invalid)?);
+    }
+    Ok(Self { fields })
+  }
+
+  pub fn load(path: &Path) -> io::Result<Self> {
+    Self::parse(&fs::read_to_string(path)?)
+  }
+
+  /// The rules of day 4 part two, from `schemas/passport.toml`. `DayFour` checks the same rules
+  /// on typed passports.
+  pub fn passport() -> Self {
+    Self::parse(PASSPORT).expect("the passport schema is valid")
+  }
+
+  pub fn fields(&self) -> &[Field] {
+    &self.fields
+  }
+
+  /// Ok or why `value` is invalid for the field `key`. Values that do not even have the right
+  /// shape, such as a range field that is not a number, are invalid too.
+  pub fn check_value(&self, key: &str, value: &str) -> Check {
+    let field = match self.fields.iter().find(|field| field.key == key) {
+      Some(field) => field,
+      None => return Err("unknown field".to_string()),
+    };
+    let not_integer = || format!("{:?} is not an integer", value);
+    let in_range = |num: i64, range: &RangeInclusive<i64>| {
+      if range.contains(&num) {
+        Ok(())
+      } else {
+        Err(format!("{} outside {}..={}", value, range.start(), range.end()))
+      }
+    };
+
+    match &field.rule {
+      Rule::Any => Ok(()),
+      Rule::Range(range) => in_range(value.parse().map_err(|_| not_integer())?, range),
+      Rule::Pattern(regex, expected) => {
+        if regex.is_match(value) {
+          Ok(())
+        } else {
+          Err(format!("{} is not {}", value, expected))
+        }
+      }
+      Rule::OneOf(values) => {
+        if values.iter().any(|allowed| allowed == value) {
+          Ok(())
+        } else {
+          Err(format!("{} is not one of {}", value, values.join(" ")))
+        }
+      }
+      Rule::Units(units) => {
+        let digits = value.len() - value.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let (num, unit) = value.split_at(digits);
+        let num: i64 = num
+          .parse()
+          .map_err(|_| format!("{:?} is not an integer and a unit", value))?;
+        if unit.is_empty() {
+          return Err(format!("{} has no unit", value));
+        }
+        match units.get(unit) {
+          Some(range) => in_range(num, range),
+          None => {
+            let known: Vec<&str> = units.keys().map(String::as_str).collect();
+            Err(format!("{:?} is not one of the units {}", unit, known.join(" ")))
+          }
+        }
+      }
+    }
+  }
+
+  /// Whether a document with the distinct `keys` has every required field.
+  pub fn check_keys(&self, keys: &HashSet<&str>) -> Check {
+    let missing: Vec<&str> = self
+      .fields
+      .iter()
+      .filter(|field| field.required && !keys.contains(field.key.as_str()))
+      .map(|field| field.key.as_str())
+      .collect();
+    if missing.is_empty() {
+      Ok(())
+    } else {
+      Err(format!("missing {}", missing.join(", ")))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use crate::schema::Schema;
+
+  #[test]
+  fn passport() {
+    let schema = Schema::passport();
+    assert_eq!(schema.fields().len(), 8);
+    assert_eq!(schema.check_value("iyr", "2010"), Ok(()));
+    assert_eq!(schema.check_value("cid", "anything"), Ok(()));
+    assert_eq!(schema.check_value("xyz", "1"), Err("unknown field".to_string()));
+    assert_eq!(
+      schema.check_value("hgt", "60ft"),
+      Err("\"ft\" is not one of the units cm in".to_string())
+    );
+    assert_eq!(
+      schema.check_value("hgt", "cm"),
+      Err("\"cm\" is not an integer and a unit".to_string())
+    );
+    assert_eq!(
+      schema.check_value("byr", "abc"),
+      Err("\"abc\" is not an integer".to_string())
+    );
+
+    let keys: HashSet<&str> = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"].into();
+    assert_eq!(schema.check_keys(&keys), Ok(()));
+    let keys: HashSet<&str> = ["byr", "cid"].into();
+    assert_eq!(
+      schema.check_keys(&keys),
+      Err("missing iyr, eyr, hgt, hcl, ecl, pid".to_string())
+    );
+  }
+
+  #[test]
+  fn custom() {
+    let schema = Schema::parse(
+      r#"
+        [[field]]
+        key = "size"
+        one_of = ["s", "m", "l"]
+
+        [[field]]
+        key = "code"
+        pattern = '^[A-Z]{3}$'
+        required = false
+      "#,
+    )
+    .unwrap();
+    assert_eq!(schema.check_value("size", "m"), Ok(()));
+    assert_eq!(
+      schema.check_value("code", "ab"),
+      Err("ab is not a match of ^[A-Z]{3}$".to_string())
+    );
+    assert_eq!(schema.check_keys(&["size"].into()), Ok(()));
+  }
+
+  #[test]
+  fn invalid() {
+    let two_rules = "[[field]]\nkey = \"a\"\nrange = [1, 2]\none_of = [\"x\"]\n";
+    assert!(Schema::parse(two_rules).is_err());
+    let twice = "[[field]]\nkey = \"a\"\n[[field]]\nkey = \"a\"\n";
+    assert!(Schema::parse(twice).is_err());
+    assert!(Schema::parse("[[field]]\nkey = \"a\"\npattern = \"(\"\n").is_err());
+    assert!(Schema::parse("[[field]]\nkey = \"a\"\nmin = 3\n").is_err());
+  }
+}
@@ -0,0 +1,127 @@
+//! Per-record verdicts for days whose answer counts the valid records of the input, so a wrong
+//! count can be traced back to the records that were judged wrongly.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+/// Whether a record passed, or why not.
+pub type Check = std::result::Result<(), String>;
+
+/// The verdict on one record of the input, which spans one or more lines.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Verdict {
+  pub first_line: usize,
+  pub last_line: usize,
+  pub valid: bool,
+  /// The rule the record broke, if it is invalid.
+  pub reason: Option<String>,
+}
+
+impl Verdict {
+  pub fn new(first_line: usize, last_line: usize, check: Check) -> Self {
+    Self {
+      first_line,
+      last_line,
+      valid: check.is_ok(),
+      reason: check.err(),
+    }
+  }
+
+  /// "line 3" or "lines 3-5".
+  pub fn lines(&self) -> String {
+    if self.first_line == self.last_line {
+      format!("line {}", self.first_line)
+    } else {
+      format!("lines {}-{}", self.first_line, self.last_line)
+    }
+  }
+}
+
+/// The verdicts on every record of an input for one part.
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+  pub day: u8,
+  pub part: u8,
+  pub input: String,
+  pub valid: usize,
+  pub verdicts: Vec<Verdict>,
+}
+
+impl Report {
+  pub fn new(day: u8, part: u8, input: &str, verdicts: Vec<Verdict>) -> Self {
+    Self {
+      day,
+      part,
+      input: input.to_string(),
+      valid: verdicts.iter().filter(|verdict| verdict.valid).count(),
+      verdicts,
+    }
+  }
+}
+
+/// Writes one line per record under a heading per report.
+pub fn write_text(reports: &[Report], out: &mut impl Write) -> io::Result<()> {
+  for (idx, report) in reports.iter().enumerate() {
+    if idx > 0 {
+      writeln!(out)?;
+    }
+    writeln!(
+      out,
+      "DAY {:02} part {} ({}): {} of {} valid",
+      report.day,
+      report.part,
+      report.input,
+      report.valid,
+      report.verdicts.len()
+    )?;
+    for verdict in &report.verdicts {
+      match &verdict.reason {
+        Some(reason) => writeln!(out, "{:<14} invalid  {}", verdict.lines(), reason)?,
+        None => writeln!(out, "{:<14} valid", verdict.lines())?,
+      }
+    }
+  }
+  Ok(())
+}
+
+pub fn write_json(reports: &[Report], out: &mut impl Write) -> io::Result<()> {
+  serde_json::to_writer_pretty(&mut *out, reports)?;
+  writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::validation::{self, Report, Verdict};
+
+  fn reports() -> Vec<Report> {
+    let verdicts = vec![
+      Verdict::new(1, 1, Ok(())),
+      Verdict::new(3, 5, Err("missing byr".to_string())),
+    ];
+    vec![Report::new(4, 1, "in.txt", verdicts)]
+  }
+
+  #[test]
+  fn text() {
+    let mut out = Vec::new();
+    validation::write_text(&reports(), &mut out).unwrap();
+    assert_eq!(
+      String::from_utf8(out).unwrap(),
+      "DAY 04 part 1 (in.txt): 1 of 2 valid\n\
+       line 1         valid\n\
+       lines 3-5      invalid  missing byr\n"
+    );
+  }
+
+  #[test]
+  fn json() {
+    let mut out = Vec::new();
+    validation::write_json(&reports(), &mut out).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json[0]["valid"], 1);
+    assert_eq!(json[0]["verdicts"][1]["first_line"], 3);
+    assert_eq!(json[0]["verdicts"][1]["reason"], "missing byr");
+    assert_eq!(json[0]["verdicts"][0]["reason"], serde_json::Value::Null);
+  }
+}
@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// The files that affect a day's result: its source, its input, its answers and any examples in
+/// `examples/dayNN/`.
+pub fn watched(day: u8) -> Vec<PathBuf> {
+  let mut paths = vec![
+    PathBuf::from(format!("src/day{:02}.rs", day)),
+    PathBuf::from(format!("inputs/day{:02}.txt", day)),
+    PathBuf::from(format!("answers/day{:02}.toml", day)),
+  ];
+  if let Ok(examples) = fs::read_dir(format!("examples/day{:02}", day)) {
+    let mut examples: Vec<PathBuf> = examples.flatten().map(|entry| entry.path()).collect();
+    examples.sort();
+    paths.extend(examples);
+  }
+  paths
+}
+
+/// Modification times of a set of files. Files that do not exist are left out, so creating or
+/// deleting one also counts as a change.
+#[derive(Debug, Default, PartialEq)]
+pub struct Snapshot(BTreeMap<PathBuf, SystemTime>);
+
+impl Snapshot {
+  pub fn take(paths: &[PathBuf]) -> Self {
+    let times = paths
+      .iter()
+      .filter_map(|path| {
+        let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+        Some((path.clone(), modified))
+      })
+      .collect();
+    Self(times)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use crate::watch::{self, Snapshot};
+
+  #[test]
+  fn watched() {
+    let paths = watch::watched(3);
+    assert_eq!(paths[0].to_str(), Some("src/day03.rs"));
+    assert_eq!(paths[1].to_str(), Some("inputs/day03.txt"));
+  }
+
+  #[test]
+  fn snapshot() {
+    let path = std::env::temp_dir().join(format!("aoc2020-watch-{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let paths = vec![path.clone()];
+
+    let missing = Snapshot::take(&paths);
+    assert_eq!(missing, Snapshot::default());
+
+    fs::write(&path, "1").unwrap();
+    let created = Snapshot::take(&paths);
+    assert_ne!(created, missing);
+    assert_eq!(Snapshot::take(&paths), created);
+
+    fs::remove_file(&path).unwrap();
+    assert_eq!(Snapshot::take(&paths), missing);
+  }
+}
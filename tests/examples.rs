@@ -0,0 +1,24 @@
+//! Checks every day against the examples in `examples/dayNN/`, with one test per example per
+//! part generated by `build.rs` from each day's `answers.toml`.
+
+use std::path::Path;
+
+use aoc2020::input;
+use aoc2020::log::Log;
+use aoc2020::registry;
+use aoc2020::runner;
+use aoc2020::YEAR;
+
+fn check(day: u8, file: &str, part: u8, expected: &str) {
+  let reg = registry::get(YEAR, day).unwrap_or_else(|| panic!("day {} is not registered", day));
+  let input = input::read(Path::new(file), false).unwrap_or_else(|err| panic!("{}: {}", file, err));
+
+  let outcome = runner::solve(reg.build().as_ref(), &input, Some(part), &Log::off());
+  let actual = match part {
+    1 => outcome.part_one,
+    _ => outcome.part_two,
+  };
+  assert_eq!(actual, Some(Ok(expected.to_string())));
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));